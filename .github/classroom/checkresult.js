@@ -4,11 +4,12 @@ function judge(outputFile) {
     try {
         let jsonResult = JSON.parse(outputFile);
         let points = {};
-        jsonResult.exercises.forEach(({ name, result }) => {
-            if (result) {
-                points[name] = [1,1]
+        jsonResult.exercises.forEach(({ name, result, points: total, earned_points }) => {
+            // Results written before exercises had weights are worth one point
+            if (total === undefined) {
+                points[name] = result ? [1,1] : [0,1]
             } else {
-                points[name] = [0,1]
+                points[name] = [earned_points, total]
             }
         })
         return points;
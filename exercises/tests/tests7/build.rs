@@ -1,6 +1,6 @@
-//! This is the build script for both tests7 and tests8.
+//! This is the build script for tests7.
 //!
-//! You should modify this file to make both exercises pass.
+//! You should modify this file to make the exercise pass.
 
 fn main() {
     // In tests7, we should set up an environment variable
@@ -15,10 +15,4 @@ fn main() {
         timestamp
     );
     println!("cargo:{}", your_command);
-
-    // In tests8, we should enable "pass" feature to make the
-    // testcase return early. Fill in the command to tell
-    // Cargo about that.
-    let your_command = "rustc-cfg=feature=\"pass\"";
-    println!("cargo:{}", your_command);
 }
@@ -6,8 +6,7 @@
 //
 // Cargo does not aim to replace other build tools, but it does integrate
 // with them with custom build scripts called `build.rs`. This file is
-// usually placed in the root of the project, while in this case the same
-// directory of this exercise.
+// placed in the root of the package, next to its `Cargo.toml`.
 //
 // It can be used to:
 //
@@ -28,8 +27,8 @@
 // In this exercise, we look for an environment variable and expect it to
 // fall in a range. You can look into the testcase to find out the details.
 //
-// You should NOT modify this file. Modify `build.rs` in the same directory
-// to pass this exercise.
+// You should NOT modify this file. Modify `build.rs` in the root of this
+// exercise's package to pass it.
 //
 // Execute `rustlings hint tests7` or use the `hint` watch subcommand for a
 // hint.
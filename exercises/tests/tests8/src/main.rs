@@ -1,8 +1,8 @@
 // tests8.rs
 //
-// This execrise shares `build.rs` with the previous exercise.
-// You need to add some code to `build.rs` to make both this exercise and
-// the previous one work.
+// Like the previous exercise, this one comes with a build script. You need
+// to add some code to `build.rs` in this exercise's directory to make it
+// work.
 //
 // Execute `rustlings hint tests8` or use the `hint` watch subcommand for a
 // hint.
@@ -0,0 +1,13 @@
+// functions1.rs
+//
+// Execute `rustlings hint functions1` or use the `hint` watch subcommand for a
+// hint.
+
+
+fn main() {
+    call_me();
+}
+
+fn call_me() {
+
+}
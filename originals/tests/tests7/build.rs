@@ -0,0 +1,18 @@
+//! This is the build script for tests7.
+//!
+//! You should modify this file to make the exercise pass.
+
+fn main() {
+    // In tests7, we should set up an environment variable
+    // called `TEST_FOO`. Print in the standard output to let
+    // Cargo do it.
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs(); // What's the use of this timestamp here?
+    let your_command = format!(
+        "rustc-env=TEST_FOO={}",
+        timestamp
+    );
+    println!("cargo:{}", your_command);
+}
@@ -0,0 +1,11 @@
+//! This is the build script for tests8.
+//!
+//! You should modify this file to make the exercise pass.
+
+fn main() {
+    // In tests8, we should enable "pass" feature to make the
+    // testcase return early. Fill in the command to tell
+    // Cargo about that.
+    let your_command = "rustc-cfg=feature=\"pass\"";
+    println!("cargo:{}", your_command);
+}
@@ -0,0 +1,23 @@
+// tests8.rs
+//
+// Like the previous exercise, this one comes with a build script. You need
+// to add some code to `build.rs` in this exercise's directory to make it
+// work.
+//
+// Execute `rustlings hint tests8` or use the `hint` watch subcommand for a
+// hint.
+
+fn main() {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_success() {
+        #[cfg(feature = "pass")]
+        return;
+
+        panic!("no cfg set");
+    }
+}
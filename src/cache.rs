@@ -0,0 +1,141 @@
+use crate::exercise::Exercise;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+// Where the outcomes of verified exercises are kept between runs
+const CACHE_DIR: &str = "target/rustlings-cache";
+
+// Bump this whenever the way exercises are verified changes, so that
+// outcomes recorded by an older rustlings are never trusted
+const CACHE_VERSION: &str = "1";
+
+// The last outcome of verifying an exercise
+#[derive(Deserialize, Serialize)]
+pub struct Entry {
+    // The hash of everything the outcome depends on
+    key: String,
+    pub passed: bool,
+    // What the exercise printed, for compile exercises
+    pub output: Option<String>,
+}
+
+// Remembers which exercises passed, so that unchanged exercises don't have
+// to be compiled and run again. An exercise is verified again as soon as
+// its source, its entry in info.toml or the toolchain changes.
+pub struct Cache {
+    dir: PathBuf,
+    // The output of `rustc -vV`, or None if the cache is disabled
+    rustc_version: Option<String>,
+}
+
+impl Cache {
+    pub fn open() -> Cache {
+        let rustc_version = Command::new("rustc")
+            .arg("-vV")
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).into_owned());
+        Cache {
+            dir: PathBuf::from(CACHE_DIR),
+            rustc_version,
+        }
+    }
+
+    // A cache that never remembers anything
+    pub fn disabled() -> Cache {
+        Cache {
+            dir: PathBuf::from(CACHE_DIR),
+            rustc_version: None,
+        }
+    }
+
+    // The last outcome of verifying the exercise, if nothing changed since
+    pub fn lookup(&self, exercise: &Exercise) -> Option<Entry> {
+        let key = self.key(exercise)?;
+        let entry: Entry =
+            serde_json::from_str(&fs::read_to_string(self.entry_path(exercise)).ok()?).ok()?;
+        (entry.key == key).then_some(entry)
+    }
+
+    // Record the outcome of verifying the exercise. The cache is only an
+    // optimization, so failing to write it is not an error.
+    pub fn store(&self, exercise: &Exercise, passed: bool, output: Option<String>) {
+        let Some(key) = self.key(exercise) else {
+            return;
+        };
+        let entry = Entry {
+            key,
+            passed,
+            output,
+        };
+        if fs::create_dir_all(&self.dir).is_ok() {
+            if let Ok(json) = serde_json::to_string(&entry) {
+                let _ = fs::write(self.entry_path(exercise), json);
+            }
+        }
+    }
+
+    fn entry_path(&self, exercise: &Exercise) -> PathBuf {
+        self.dir.join(format!("{}.json", exercise.name))
+    }
+
+    // Hash the exercise's source files, its settings in info.toml and the
+    // toolchain. Returns None if a source file can't be read.
+    fn key(&self, exercise: &Exercise) -> Option<String> {
+        let rustc_version = self.rustc_version.as_ref()?;
+        let mut hasher = Sha256::new();
+        hasher.update(CACHE_VERSION);
+        hasher.update(rustc_version);
+        // Every setting of the exercise, including its mode
+        hasher.update(format!("{exercise:?}"));
+        hasher.update(exercise.source_hash()?);
+        Some(
+            hasher
+                .finalize()
+                .iter()
+                .map(|byte| format!("{byte:02x}"))
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::exercise::Mode;
+    use std::env;
+
+    #[test]
+    fn test_cache_key_follows_source() {
+        let dir = env::temp_dir().join(format!("rustlings-cache-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("cached.rs");
+        fs::write(&path, "fn main() {}\n").unwrap();
+        let exercise = Exercise {
+            name: String::from("cached"),
+            path: path.clone(),
+            mode: Mode::Compile,
+            ..Default::default()
+        };
+        let cache = Cache {
+            dir: dir.join("cache"),
+            rustc_version: Some(String::from("rustc 1.0.0")),
+        };
+
+        assert!(cache.lookup(&exercise).is_none());
+        cache.store(&exercise, true, Some(String::from("Hello")));
+        let entry = cache.lookup(&exercise).unwrap();
+        assert!(entry.passed);
+        assert_eq!(entry.output.as_deref(), Some("Hello"));
+
+        fs::write(&path, "fn main() { println!(\"changed\"); }\n").unwrap();
+        assert!(cache.lookup(&exercise).is_none());
+        assert!(Cache::disabled().lookup(&exercise).is_none());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
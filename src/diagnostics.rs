@@ -0,0 +1,231 @@
+use console::style;
+use serde::Deserialize;
+
+// The arguments that make rustc print its diagnostics as JSON, one per
+// line, while still rendering the human readable (and colored) version
+// into each diagnostic's `rendered` field
+pub const RUSTC_JSON_ARGS: &[&str] = &["--error-format=json", "--json=diagnostic-rendered-ansi"];
+
+// A diagnostic as emitted by `rustc --error-format=json`.
+// See https://doc.rust-lang.org/rustc/json.html
+#[derive(Deserialize, Debug, Clone)]
+pub struct Diagnostic {
+    pub message: String,
+    pub code: Option<DiagnosticCode>,
+    pub level: Level,
+    pub spans: Vec<Span>,
+    pub children: Vec<Diagnostic>,
+    // The diagnostic as rustc would have printed it on its own
+    pub rendered: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct DiagnosticCode {
+    // The error code, like `E0308`
+    pub code: String,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Level {
+    Error,
+    Warning,
+    Note,
+    Help,
+    FailureNote,
+    #[serde(other)]
+    Other,
+}
+
+// A region of source code a diagnostic points at
+#[derive(Deserialize, Debug, Clone)]
+pub struct Span {
+    pub file_name: String,
+    pub line_start: usize,
+    pub line_end: usize,
+    pub column_start: usize,
+    pub is_primary: bool,
+    // The source lines the span covers
+    pub text: Vec<SpanLine>,
+    pub label: Option<String>,
+    // The code rustc suggests to replace the span with
+    pub suggested_replacement: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct SpanLine {
+    pub text: String,
+    // The 1-based, end exclusive columns of the span on this line
+    pub highlight_start: usize,
+    pub highlight_end: usize,
+}
+
+impl Diagnostic {
+    // The error code, if the diagnostic has one `rustc --explain` knows.
+    // Lints that were turned into errors carry their name instead.
+    pub fn code(&self) -> Option<&str> {
+        self.code
+            .as_ref()
+            .map(|c| c.code.as_str())
+            .filter(|code| code.starts_with('E'))
+    }
+
+    // Whether this is an error about the code, rather than a summary like
+    // "aborting due to 2 previous errors"
+    fn is_code_error(&self) -> bool {
+        self.level == Level::Error && !self.spans.is_empty()
+    }
+}
+
+// Split rustc's JSON stderr into diagnostics and the rendered text rustc
+// would have printed without `--error-format=json`. Lines that aren't
+// diagnostics, like linker errors, are kept as they are.
+pub fn parse(stderr: &str) -> (Vec<Diagnostic>, String) {
+    let mut diagnostics = Vec::new();
+    let mut rendered = String::new();
+    for line in stderr.lines() {
+        match serde_json::from_str::<Diagnostic>(line) {
+            Ok(diagnostic) => {
+                if let Some(text) = &diagnostic.rendered {
+                    rendered.push_str(text);
+                }
+                diagnostics.push(diagnostic);
+            }
+            Err(_) => {
+                rendered.push_str(line);
+                rendered.push('\n');
+            }
+        }
+    }
+    (diagnostics, rendered)
+}
+
+// The code of the first error, for `rustc --explain`
+pub fn first_error_code(diagnostics: &[Diagnostic]) -> Option<&str> {
+    diagnostics
+        .iter()
+        .find(|d| d.is_code_error())
+        .and_then(Diagnostic::code)
+}
+
+// Render only the first error: its message, the source it points at and
+// rustc's suggestions. Returns None if there is no such error to show.
+pub fn render_focused(diagnostics: &[Diagnostic]) -> Option<String> {
+    let mut errors = diagnostics.iter().filter(|d| d.is_code_error());
+    let error = errors.next()?;
+    let hidden = errors.count();
+
+    let mut out = String::new();
+    let header = match error.code() {
+        Some(code) => format!("error[{code}]: {}", error.message),
+        None => format!("error: {}", error.message),
+    };
+    out.push_str(&format!("{}\n", style(header).red().bold()));
+
+    let span = error
+        .spans
+        .iter()
+        .find(|s| s.is_primary)
+        .unwrap_or(&error.spans[0]);
+    render_span(&mut out, span);
+
+    for child in &error.children {
+        let suggestion = child
+            .spans
+            .iter()
+            .find_map(|s| s.suggested_replacement.as_deref());
+        let line = match (child.level, suggestion) {
+            (Level::Help, Some(replacement)) if !replacement.is_empty() => {
+                format!("{}: {}: `{replacement}`", style("help").cyan().bold(), child.message)
+            }
+            (Level::Help, _) => format!("{}: {}", style("help").cyan().bold(), child.message),
+            (Level::Note, _) => format!("{}: {}", style("note").bold(), child.message),
+            _ => continue,
+        };
+        out.push_str(&line);
+        out.push('\n');
+    }
+
+    if let Some(code) = error.code() {
+        out.push_str(&format!("\n{}\n", t!("diagnostics.explain", code = code)));
+    }
+    if hidden > 0 {
+        out.push_str(&format!(
+            "{}\n",
+            style(t!("diagnostics.hidden", hidden = hidden)).dim()
+        ));
+    }
+    Some(out)
+}
+
+// Print the location of a span and its source lines with the span underlined
+fn render_span(out: &mut String, span: &Span) {
+    let width = span.line_end.to_string().len();
+    let gutter = format!("{:width$} {}", "", style("|").blue().bold());
+    out.push_str(&format!(
+        "{:width$}{} {}:{}:{}\n",
+        "",
+        style("-->").blue().bold(),
+        span.file_name,
+        span.line_start,
+        span.column_start
+    ));
+    out.push_str(&format!("{gutter}\n"));
+    let last = span.text.len().saturating_sub(1);
+    for (i, line) in span.text.iter().enumerate() {
+        out.push_str(&format!(
+            "{} {} {}\n",
+            style(format!("{:>width$}", span.line_start + i)).blue().bold(),
+            style("|").blue().bold(),
+            line.text
+        ));
+        let start = line.highlight_start.saturating_sub(1);
+        let len = line.highlight_end.saturating_sub(line.highlight_start).max(1);
+        let mut underline = format!("{}{}", " ".repeat(start), "^".repeat(len));
+        if i == last {
+            if let Some(label) = &span.label {
+                underline.push(' ');
+                underline.push_str(label);
+            }
+        }
+        out.push_str(&format!("{gutter} {}\n", style(underline).red().bold()));
+    }
+    out.push_str(&format!("{gutter}\n"));
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // Trimmed down output of `rustc --error-format=json` for a type mismatch
+    const STDERR: &str = concat!(
+        r#"{"$message_type":"diagnostic","message":"mismatched types","code":{"code":"E0308","explanation":"..."},"level":"error","spans":[{"file_name":"variables.rs","byte_start":30,"byte_end":37,"line_start":2,"line_end":2,"column_start":18,"column_end":25,"is_primary":true,"text":[{"text":"    let x: i32 = \"hello\";","highlight_start":18,"highlight_end":25}],"label":"expected `i32`, found `&str`","suggested_replacement":null,"suggestion_applicability":null,"expansion":null}],"children":[],"rendered":"error[E0308]: mismatched types\n"}"#,
+        "\n",
+        r#"{"$message_type":"diagnostic","message":"aborting due to 1 previous error","code":null,"level":"error","spans":[],"children":[],"rendered":"error: aborting due to 1 previous error\n"}"#,
+        "\n",
+        "a line that isn't JSON\n",
+    );
+
+    #[test]
+    fn test_parse() {
+        let (diagnostics, rendered) = parse(STDERR);
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].level, Level::Error);
+        assert_eq!(first_error_code(&diagnostics), Some("E0308"));
+        assert_eq!(
+            rendered,
+            "error[E0308]: mismatched types\nerror: aborting due to 1 previous error\na line that isn't JSON\n"
+        );
+    }
+
+    #[test]
+    fn test_render_focused() {
+        let (diagnostics, _) = parse(STDERR);
+        let focused = console::strip_ansi_codes(&render_focused(&diagnostics).unwrap()).into_owned();
+        assert!(focused.starts_with("error[E0308]: mismatched types\n --> variables.rs:2:18\n"));
+        assert!(focused.contains("2 |     let x: i32 = \"hello\";\n"));
+        assert!(focused.contains("  |                  ^^^^^^^ expected `i32`, found `&str`\n"));
+        assert!(focused.contains("rustc --explain E0308"));
+        assert!(render_focused(&diagnostics[1..]).is_none());
+    }
+}
@@ -0,0 +1,85 @@
+use crate::exercise::{trim_output, Exercise, ExpectedOutput};
+use console::style;
+use std::fmt::Write;
+
+// Outputs longer than this are cut off before diffing them
+const MAX_DIFF_LINES: usize = 1000;
+
+// Show how the output of an exercise differs from its expected output
+pub fn output_diff(exercise: &Exercise, stdout: &str) -> String {
+    let mut out = String::new();
+    match &exercise.expected_output {
+        Some(ExpectedOutput::Exact(expected)) => diff_lines(expected, stdout, &mut out),
+        Some(ExpectedOutput::Trimmed(expected)) => {
+            diff_lines(&trim_output(expected), &trim_output(stdout), &mut out)
+        }
+        Some(ExpectedOutput::Regex(pattern)) => {
+            let _ = writeln!(
+                out,
+                "{}",
+                t!("diff.expected_matching", pattern = style(pattern).bold())
+            );
+            let _ = writeln!(out, "{}", t!("diff.printed_instead"));
+            let _ = write!(out, "{stdout}");
+        }
+        None => {}
+    }
+    out
+}
+
+// A line by line diff: lines that were expected but not printed are
+// marked with `-`, lines that were printed but not expected with `+`
+fn diff_lines(expected: &str, actual: &str, out: &mut String) {
+    let expected: Vec<&str> = expected.split('\n').take(MAX_DIFF_LINES).collect();
+    let actual: Vec<&str> = actual.split('\n').take(MAX_DIFF_LINES).collect();
+
+    // common[i][j] is the length of the longest common subsequence
+    // of expected[i..] and actual[j..]
+    let mut common = vec![vec![0; actual.len() + 1]; expected.len() + 1];
+    for i in (0..expected.len()).rev() {
+        for j in (0..actual.len()).rev() {
+            common[i][j] = if expected[i] == actual[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let _ = writeln!(
+        out,
+        "{} {}",
+        style(format!("- {}", t!("diff.expected"))).red(),
+        style(format!("+ {}", t!("diff.printed"))).green()
+    );
+    let (mut i, mut j) = (0, 0);
+    while i < expected.len() || j < actual.len() {
+        if i < expected.len() && j < actual.len() && expected[i] == actual[j] {
+            let _ = writeln!(out, "  {}", expected[i]);
+            i += 1;
+            j += 1;
+        } else if i < expected.len() && (j == actual.len() || common[i + 1][j] >= common[i][j + 1])
+        {
+            let _ = writeln!(out, "{}", style(format!("- {}", expected[i])).red());
+            i += 1;
+        } else {
+            let _ = writeln!(out, "{}", style(format!("+ {}", actual[j])).green());
+            j += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_diff_lines() {
+        let mut out = String::new();
+        diff_lines("Hello\nworld\n!", "Hello\nthere\n!", &mut out);
+        assert_eq!(
+            console::strip_ansi_codes(&out),
+            "- expected + printed\n  Hello\n- world\n+ there\n  !\n"
+        );
+    }
+}
@@ -1,35 +1,108 @@
+use crate::diagnostics::{self, Diagnostic, RUSTC_JSON_ARGS};
+use crate::i18n::Localized;
+use crate::process::{output_with_input, output_with_timeout};
+use crate::sandbox::Sandbox;
 use regex::Regex;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::env;
 use std::fmt::{self, Display, Formatter};
-use std::fs::{self, remove_file, File};
-use std::io::Read;
-use std::path::PathBuf;
-use std::process::{self, Command};
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::iter;
+use std::path::{Path, PathBuf};
+use std::process::{self, Command, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::OnceLock;
+use std::time::Duration;
 
 const RUSTC_COLOR_ARGS: &[&str] = &["--color", "always"];
-const RUSTC_EDITION_ARGS: &[&str] = &["--edition", "2021"];
+pub const DEFAULT_EDITION: &str = "2021";
 const I_AM_DONE_REGEX: &str = r"(?m)^\s*///?\s*I\s+AM\s+NOT\s+DONE";
 const CONTEXT: usize = 2;
-const CLIPPY_CARGO_TOML_PATH: &str = "./exercises/clippy/Cargo.toml";
-const BUILD_SCRIPT_CARGO_TOML_PATH: &str = "./exercises/tests/Cargo.toml";
+const DEFAULT_TIMEOUT_SECS: u64 = 60;
+// Miri interprets the tests instead of running them natively, which takes
+// many times longer
+const MIRI_SLOWDOWN: u32 = 10;
+// What Miri reports when it finds undefined behavior or a leak. Anything
+// else that makes it fail is a problem with Miri, not with the exercise.
+const MIRI_ERRORS: &[&str] = &["error: Undefined Behavior", "error: memory leaked"];
 
-// Get a temporary file name that is hopefully unique
+// Get the root of this process's scratch space. Every exercise gets its own
+// subdirectory below it, so exercises compiled in parallel never share
+// binaries or generated Cargo manifests.
 #[inline]
-fn temp_file() -> String {
-    let thread_id: String = format!("{:?}", std::thread::current().id())
-        .chars()
-        .filter(|c| c.is_alphanumeric())
-        .collect();
+fn scratch_root() -> PathBuf {
+    env::temp_dir().join(format!("rustlings-{}", process::id()))
+}
+
+// How many `ScratchRoot`s are alive. While there are any, exercises leave
+// the scratch root alone when they clean up after themselves.
+static SCRATCH_ROOT_HOLDERS: AtomicUsize = AtomicUsize::new(0);
+
+// Keeps the scratch root in place while exercises are compiled in parallel,
+// so that one finishing can't remove it from under another one starting.
+// The root is removed once the last of these is dropped.
+pub struct ScratchRoot(());
+
+impl ScratchRoot {
+    pub fn create() -> io::Result<ScratchRoot> {
+        SCRATCH_ROOT_HOLDERS.fetch_add(1, Ordering::SeqCst);
+        let root = ScratchRoot(());
+        fs::create_dir_all(scratch_root())?;
+        Ok(root)
+    }
+}
+
+impl Drop for ScratchRoot {
+    fn drop(&mut self) {
+        if SCRATCH_ROOT_HOLDERS.fetch_sub(1, Ordering::SeqCst) == 1 {
+            let _ignored = fs::remove_dir_all(scratch_root());
+        }
+    }
+}
+
+// Render a path as a TOML string for the generated Cargo manifests
+fn toml_path(path: &Path) -> String {
+    toml::Value::String(path.display().to_string()).to_string()
+}
 
-    format!("./temp_{}_{thread_id}", process::id())
+// Whether a file or directory name is something Cargo writes into a package
+fn is_build_output(name: &std::ffi::OsStr) -> bool {
+    name == "target" || name == "Cargo.lock"
+}
+
+// Every file below the directory, sorted, leaving out Cargo's build output
+// and lock files
+pub fn files_below(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut dirs = vec![dir.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if is_build_output(&entry.file_name()) {
+                continue;
+            }
+            if path.is_dir() {
+                dirs.push(path);
+            } else {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    files
 }
 
 // The mode of the exercise.
-#[derive(Deserialize, Copy, Clone, Debug)]
+#[derive(Deserialize, Serialize, Copy, Clone, Debug, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum Mode {
     // Indicates that the exercise should be compiled as a binary
+    #[default]
     Compile,
     // Indicates that the exercise should be compiled as a test harness
     Test,
@@ -37,16 +110,52 @@ pub enum Mode {
     Clippy,
     // Indicates that the exercise should be run using cargo with build script
     BuildScript,
+    // Indicates that the exercise is a Cargo package with its own manifest
+    Cargo,
+}
+
+// What Cargo does with an exercise of the Cargo mode
+#[derive(Deserialize, Copy, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum CargoCommand {
+    // `cargo test`: the exercise passes when its tests do
+    #[default]
+    Test,
+    // `cargo run`: the exercise passes when it runs successfully
+    Run,
+    // `cargo clippy`: the exercise passes when Clippy has nothing to say
+    Clippy,
 }
 
 #[derive(Deserialize)]
 pub struct ExerciseList {
     pub exercises: Vec<Exercise>,
+    // The timeout in seconds for exercises that don't set their own
+    #[serde(default)]
+    pub timeout: Option<u64>,
+    // The exercises whose tests learners are meant to edit, and which
+    // therefore have no `tests_sha256`
+    #[serde(default)]
+    pub editable_tests: Vec<String>,
+}
+
+impl ExerciseList {
+    // Hand out the exercises with the list-wide defaults applied
+    pub fn into_exercises(self) -> Vec<Exercise> {
+        let timeout = self.timeout;
+        self.exercises
+            .into_iter()
+            .map(|e| Exercise {
+                timeout: e.timeout.or(timeout),
+                ..e
+            })
+            .collect()
+    }
 }
 
 // A representation of a rustlings exercise.
 // This is deserialized from the accompanying info.toml file
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Clone, Debug, Default)]
 pub struct Exercise {
     // Name of the exercise
     pub name: String,
@@ -54,8 +163,89 @@ pub struct Exercise {
     pub path: PathBuf,
     // The mode of the exercise (Test, Compile, or Clippy)
     pub mode: Mode,
-    // The hint text associated with the exercise
-    pub hint: String,
+    // The hint text associated with the exercise, in one or more locales
+    #[serde(default)]
+    pub hint: Localized,
+    // Further hints, revealed one at a time after `hint`
+    #[serde(default)]
+    pub hints: Vec<Localized>,
+    // The number of seconds compiling or running the exercise may take
+    // before it is stopped, overriding the default of the exercise list
+    #[serde(default)]
+    pub timeout: Option<u64>,
+    // How many points the exercise is worth when grading, 1 by default
+    #[serde(default)]
+    pub points: Option<u32>,
+    // The category the exercise is graded in, by default the directory
+    // it lives in
+    #[serde(default)]
+    pub category: Option<String>,
+    // The names of the exercises that have to be done before this one
+    #[serde(default)]
+    pub requires: Vec<String>,
+    // What a compile mode exercise has to print to pass
+    #[serde(default)]
+    pub expected_output: Option<ExpectedOutput>,
+    // The text fed to the exercise's standard input when it runs
+    #[serde(default)]
+    pub stdin: Option<String>,
+    // The Rust edition the exercise is compiled with, 2021 by default
+    #[serde(default)]
+    pub edition: Option<String>,
+    // Extra arguments for rustc
+    #[serde(default)]
+    pub rustc_args: Vec<String>,
+    // Configuration options set with `--cfg`, like `feature="fast"`
+    #[serde(default)]
+    pub cfg: Vec<String>,
+    // Lints that are turned into errors
+    #[serde(default)]
+    pub deny: Vec<String>,
+    // What Cargo does with an exercise of the Cargo mode, `test` by default
+    #[serde(default)]
+    pub cargo: Option<CargoCommand>,
+    // Whether the tests also have to pass under Miri, which catches
+    // undefined behavior and memory leaks
+    #[serde(default)]
+    pub miri: bool,
+    // The hash of the exercise's test modules as shipped. Grading fails
+    // the exercise if its tests were changed.
+    #[serde(default)]
+    pub tests_sha256: Option<String>,
+}
+
+// How the output of an exercise is compared to what was expected
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum ExpectedOutput {
+    // Exactly this text
+    Exact(String),
+    // This text, ignoring whitespace at the start and end of the output
+    // and at the end of every line
+    Trimmed(String),
+    // Any text the regular expression matches
+    Regex(String),
+}
+
+impl ExpectedOutput {
+    pub fn matches(&self, stdout: &str) -> bool {
+        match self {
+            ExpectedOutput::Exact(expected) => stdout == expected,
+            ExpectedOutput::Trimmed(expected) => trim_output(stdout) == trim_output(expected),
+            ExpectedOutput::Regex(pattern) => {
+                Regex::new(pattern).is_ok_and(|regex| regex.is_match(stdout))
+            }
+        }
+    }
+}
+
+// Strip the whitespace that `ExpectedOutput::Trimmed` ignores
+pub fn trim_output(text: &str) -> String {
+    text.trim()
+        .lines()
+        .map(str::trim_end)
+        .collect::<Vec<_>>()
+        .join("\n")
 }
 
 // An enum to track of the state of an Exercise.
@@ -93,160 +283,585 @@ impl<'a> CompiledExercise<'a> {
 }
 
 // A representation of an already executed binary
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct ExerciseOutput {
     // The textual contents of the standard output of the binary
     pub stdout: String,
     // The textual contents of the standard error of the binary
     pub stderr: String,
+    // Whether the command was killed for exceeding the exercise's timeout
+    pub timed_out: bool,
+    // The diagnostics of a failed rustc invocation. Its `stderr` holds them
+    // rendered the way rustc would have printed them.
+    pub diagnostics: Vec<Diagnostic>,
+    // Whether the exercise ran fine, but didn't print its expected output
+    pub unexpected_output: bool,
+    // How the tests fared under Miri. When Miri failed, `stderr` holds
+    // what it reported.
+    pub miri: MiriOutcome,
+}
+
+// What came of running an exercise's tests under Miri
+#[derive(PartialEq, Clone, Copy, Debug, Default)]
+pub enum MiriOutcome {
+    // The exercise doesn't ask for Miri, or didn't get that far
+    #[default]
+    NotRun,
+    // Miri had nothing to complain about
+    Passed,
+    // Miri isn't installed or couldn't run, so the exercise was only
+    // tested without it
+    Unavailable,
+    // Miri found undefined behavior or a leak
+    Failed,
+}
+
+// What came of verifying an exercise
+#[derive(Debug)]
+pub enum Attempt {
+    // It passed before and hasn't changed since, and printed this back then
+    Cached(Option<String>),
+    // It didn't compile, or Clippy rejected it
+    CompileFailed(ExerciseOutput),
+    // It compiled and ran. Exercises that are only linted pass once they
+    // compile, without any output.
+    Ran(Result<ExerciseOutput, ExerciseOutput>),
 }
 
-struct FileHandle;
+impl Attempt {
+    pub fn passed(&self) -> bool {
+        matches!(self, Attempt::Cached(_) | Attempt::Ran(Ok(_)))
+    }
+}
+
+// Tell undefined behavior and leaks that Miri found apart from Miri itself
+// failing, like when it can't build or doesn't support what a test does
+fn miri_outcome(success: bool, reported: &str) -> MiriOutcome {
+    let reported = console::strip_ansi_codes(reported);
+    if success {
+        MiriOutcome::Passed
+    } else if MIRI_ERRORS.iter().any(|error| reported.contains(error)) {
+        MiriOutcome::Failed
+    } else {
+        MiriOutcome::Unavailable
+    }
+}
+
+// Whether `cargo +nightly miri` works. Setting Miri up builds the standard
+// library for it the first time, which would otherwise count against the
+// timeout of the first exercise run under Miri. Done only once, since Cargo
+// takes a while to answer.
+fn miri_available() -> bool {
+    static AVAILABLE: OnceLock<bool> = OnceLock::new();
+    *AVAILABLE.get_or_init(|| {
+        Command::new("cargo")
+            .args(["+nightly", "miri", "setup"])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .is_ok_and(|status| status.success())
+    })
+}
+
+// Owns the scratch directory of a compiled exercise and removes it once
+// the exercise is no longer needed
+struct FileHandle {
+    scratch_dir: PathBuf,
+}
 
 impl Drop for FileHandle {
     fn drop(&mut self) {
-        clean();
+        clean(&self.scratch_dir);
     }
 }
 
 impl Exercise {
-    pub fn compile(&self) -> Result<CompiledExercise<'_>, ExerciseOutput> {
-        let cmd = match self.mode {
-            Mode::Compile => Command::new("rustc")
-                .args(&[self.path.to_str().unwrap(), "-o", &temp_file()])
-                .args(RUSTC_COLOR_ARGS)
-                .args(RUSTC_EDITION_ARGS)
-                .output(),
-            Mode::Test => Command::new("rustc")
-                .args(&["--test", self.path.to_str().unwrap(), "-o", &temp_file()])
-                .args(RUSTC_COLOR_ARGS)
-                .args(RUSTC_EDITION_ARGS)
-                .output(),
-            Mode::Clippy => {
-                let cargo_toml = format!(
-                    r#"[package]
+    // The directory holding this exercise's binary and generated manifest
+    fn scratch_dir(&self) -> PathBuf {
+        scratch_root().join(&self.name)
+    }
+
+    // How long a single compile or run of this exercise may take
+    pub fn timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout.unwrap_or(DEFAULT_TIMEOUT_SECS))
+    }
+
+    // All hints of the exercise, in the order they are revealed
+    pub fn hints(&self) -> Vec<&str> {
+        iter::once(&self.hint)
+            .chain(&self.hints)
+            .map(Localized::get)
+            .filter(|h| !h.trim().is_empty())
+            .collect()
+    }
+
+    pub fn points(&self) -> u32 {
+        self.points.unwrap_or(1)
+    }
+
+    // The category of the exercise. Exercises at the top of the exercises
+    // tree, like the quizzes, are grouped by their name without the number.
+    pub fn category(&self) -> String {
+        if let Some(category) = &self.category {
+            return category.clone();
+        }
+        match self.path.parent().and_then(|p| p.file_name()) {
+            Some(dir) if dir != "exercises" => dir.to_string_lossy().into_owned(),
+            _ => self
+                .name
+                .trim_end_matches(|c: char| c.is_ascii_digit())
+                .to_string(),
+        }
+    }
+
+    // The path of the binary rustc produces for this exercise
+    fn temp_file(&self) -> PathBuf {
+        self.scratch_dir().join(&self.name)
+    }
+
+    // The Rust edition the exercise is compiled with
+    pub fn edition(&self) -> &str {
+        self.edition.as_deref().unwrap_or(DEFAULT_EDITION)
+    }
+
+    // The flags for rustc besides the edition: the cfg options, the denied
+    // lints and the exercise's own arguments
+    fn rustc_flags(&self) -> Vec<String> {
+        let mut flags = Vec::new();
+        for cfg in &self.cfg {
+            flags.extend([String::from("--cfg"), cfg.clone()]);
+        }
+        for lint in &self.deny {
+            flags.extend([String::from("-D"), lint.clone()]);
+        }
+        flags.extend(self.rustc_args.iter().cloned());
+        flags
+    }
+
+    // The file rustc compiles: the exercise file itself, or the `main.rs`,
+    // or failing that the `lib.rs`, of an exercise that is a directory.
+    // Those of a Cargo package live in its `src` directory.
+    pub fn root(&self) -> PathBuf {
+        if !self.path.is_dir() {
+            return self.path.clone();
+        }
+        let dir = match self.mode {
+            Mode::Cargo => self.path.join("src"),
+            _ => self.path.clone(),
+        };
+        let main = dir.join("main.rs");
+        if main.exists() {
+            main
+        } else {
+            dir.join("lib.rs")
+        }
+    }
+
+    pub fn cargo_command(&self) -> CargoCommand {
+        self.cargo.unwrap_or_default()
+    }
+
+    // A Cargo invocation on the exercise's own manifest. Nothing is
+    // downloaded, so the exercise can only depend on packages by path, and
+    // everything is built in the exercise's scratch directory.
+    fn cargo(&self, args: &[&str]) -> Command {
+        let mut command = Command::new("cargo");
+        command
+            .args(args)
+            .arg("--manifest-path")
+            .arg(self.manifest())
+            .arg("--target-dir")
+            .arg(self.scratch_dir().join("target"))
+            .arg("--offline")
+            .args(RUSTC_COLOR_ARGS);
+        self.set_rustflags(&mut command);
+        command
+    }
+
+    // The manifest of a Cargo exercise. The path is absolute, since Cargo
+    // runs in the sandbox's working directory.
+    fn manifest(&self) -> PathBuf {
+        env::current_dir()
+            .expect("Failed to get the current directory")
+            .join(&self.path)
+            .join("Cargo.toml")
+    }
+
+    // Inside the sandbox the project is read-only, so Cargo couldn't write
+    // the lock file of a Cargo exercise there. It's brought up to date
+    // beforehand, which doesn't run any of the exercise's code. Should
+    // this fail, the build reports why.
+    fn update_lock_file(&self) {
+        let mut command = Command::new("cargo");
+        command
+            .args(["generate-lockfile", "--offline", "--manifest-path"])
+            .arg(self.manifest());
+        let _ = output_with_timeout(&mut command, self.timeout());
+    }
+
+    // The sandbox for the processes running the learner's code. The CPU
+    // limit leaves room for the timeout to stop a busy loop first, so that
+    // it's reported as one. A limit of 0 would kill every process at once.
+    fn sandbox(&self, timeout: Duration) -> Sandbox {
+        Sandbox {
+            work_dir: self.scratch_dir().join("work"),
+            writable: vec![self.scratch_dir()],
+            cpu_secs: 2 * timeout.as_secs().max(1),
+        }
+    }
+
+    // Cargo hands these to every rustc it runs. Unlike RUSTFLAGS, they may
+    // contain spaces.
+    fn set_rustflags(&self, command: &mut Command) {
+        let flags = self.rustc_flags();
+        if !flags.is_empty() {
+            command.env("CARGO_ENCODED_RUSTFLAGS", flags.join("\x1f"));
+        }
+    }
+
+    // The build script of a BuildScript exercise, which lives next to its root
+    fn build_script(&self) -> PathBuf {
+        env::current_dir()
+            .expect("Failed to get the current directory")
+            .join(self.root())
+            .with_file_name("build.rs")
+    }
+
+    // The files of the exercise itself: the exercise file, or every file
+    // in the exercise's directory
+    pub fn files(&self) -> Vec<PathBuf> {
+        if self.path.is_dir() {
+            files_below(&self.path)
+        } else {
+            vec![self.path.clone()]
+        }
+    }
+
+    // The files the outcome of compiling and running this exercise depends on
+    pub fn inputs(&self) -> Vec<PathBuf> {
+        let mut inputs = self.files();
+        if let Mode::BuildScript = self.mode {
+            let build_script = self.root().with_file_name("build.rs");
+            if !inputs.contains(&build_script) {
+                inputs.push(build_script);
+            }
+        }
+        inputs
+    }
+
+    // Whether a file the watcher reported belongs to this exercise. Its
+    // path has to be canonical, the way the watcher reports them.
+    pub fn owns(&self, file: &Path) -> bool {
+        if self.path.is_dir() {
+            self.path.canonicalize().is_ok_and(|dir| {
+                file.strip_prefix(dir)
+                    .is_ok_and(|relative| !relative.iter().any(is_build_output))
+            })
+        } else {
+            file.ends_with(&self.path)
+        }
+    }
+
+    // A SHA-256 hash of the exercise's inputs, or None if one can't be read
+    pub fn source_hash(&self) -> Option<String> {
+        let mut hasher = Sha256::new();
+        for input in self.inputs() {
+            let contents = fs::read(input).ok()?;
+            hasher.update((contents.len() as u64).to_le_bytes());
+            hasher.update(contents);
+        }
+        Some(
+            hasher
+                .finalize()
+                .iter()
+                .map(|byte| format!("{byte:02x}"))
+                .collect(),
+        )
+    }
+
+    // Write a Cargo manifest for this exercise into its scratch directory
+    // and return its path. `[workspace]` keeps Cargo from attaching the
+    // manifest to whatever workspace the scratch directory happens to live in.
+    fn write_cargo_toml(&self, extra: &str) -> PathBuf {
+        let source = env::current_dir()
+            .expect("Failed to get the current directory")
+            .join(self.root());
+        let cargo_toml = format!(
+            r#"[package]
 name = "{}"
 version = "0.0.1"
-edition = "2021"
+edition = {}
+{extra}
+[workspace]
+
 [[bin]]
 name = "{}"
-path = "{}.rs""#,
-                    self.name, self.name, self.name
-                );
-                let cargo_toml_error_msg = if env::var("NO_EMOJI").is_ok() {
-                    "Failed to write Clippy Cargo.toml file."
-                } else {
-                    "Failed to write 📎 Clippy 📎 Cargo.toml file."
-                };
-                fs::write(CLIPPY_CARGO_TOML_PATH, cargo_toml).expect(cargo_toml_error_msg);
+path = {}"#,
+            self.name,
+            toml::Value::String(self.edition().to_string()),
+            self.name,
+            toml_path(&source)
+        );
+        let cargo_toml_error_msg = if env::var("NO_EMOJI").is_ok() {
+            "Failed to write Clippy Cargo.toml file."
+        } else {
+            "Failed to write 📎 Clippy 📎 Cargo.toml file."
+        };
+        let cargo_toml_path = self.scratch_dir().join("Cargo.toml");
+        fs::write(&cargo_toml_path, cargo_toml).expect(cargo_toml_error_msg);
+        cargo_toml_path
+    }
+
+    // The rustc invocation building this exercise's binary or test harness
+    fn rustc_command(&self, test: bool) -> Command {
+        let mut command = Command::new("rustc");
+        if test {
+            command.arg("--test");
+        }
+        command
+            .arg(self.root())
+            .arg("-o")
+            .arg(self.temp_file())
+            .args(RUSTC_JSON_ARGS)
+            .args(["--edition", self.edition()])
+            .args(self.rustc_flags());
+        command
+    }
+
+    // Whether the exercise is only linted with Clippy, rather than run
+    pub fn only_linted(&self) -> bool {
+        match self.mode {
+            Mode::Clippy => true,
+            Mode::Cargo => self.cargo_command() == CargoCommand::Clippy,
+            Mode::Compile | Mode::Test | Mode::BuildScript => false,
+        }
+    }
+
+    // Compile the exercise and run it, unless it is only linted
+    pub fn attempt(&self) -> Attempt {
+        match self.compile() {
+            Err(output) => Attempt::CompileFailed(output),
+            Ok(_) if self.only_linted() => Attempt::Ran(Ok(ExerciseOutput::default())),
+            Ok(compiled) => Attempt::Ran(compiled.run()),
+        }
+    }
+
+    pub fn compile(&self) -> Result<CompiledExercise<'_>, ExerciseOutput> {
+        let scratch_dir = self.scratch_dir();
+        fs::create_dir_all(&scratch_dir).expect("Failed to create the scratch directory");
+        let target_dir = scratch_dir.join("target");
+
+        let mut command = match self.mode {
+            Mode::Compile => self.rustc_command(false),
+            Mode::Test => self.rustc_command(true),
+            Mode::Clippy => {
+                let cargo_toml_path = self.write_cargo_toml("");
                 // To support the ability to run the clippy exercises, build
                 // an executable, in addition to running clippy. With a
                 // compilation failure, this would silently fail. But we expect
                 // clippy to reflect the same failure while compiling later.
-                Command::new("rustc")
-                    .args(&[self.path.to_str().unwrap(), "-o", &temp_file()])
-                    .args(RUSTC_COLOR_ARGS)
-                    .args(RUSTC_EDITION_ARGS)
-                    .output()
+                output_with_timeout(&mut self.rustc_command(false), self.timeout())
                     .expect("Failed to compile!");
-                // Due to an issue with Clippy, a cargo clean is required to catch all lints.
+                // Clippy only reports lints for crates it actually checks, so
+                // the target directory must not be shared with earlier runs.
+                // A fresh one lives in the scratch directory of every compile.
                 // See https://github.com/rust-lang/rust-clippy/issues/2604
-                // This is already fixed on Clippy's master branch. See this issue to track merging into Cargo:
-                // https://github.com/rust-lang/rust-clippy/issues/3837
-                Command::new("cargo")
-                    .args(&["clean", "--manifest-path", CLIPPY_CARGO_TOML_PATH])
-                    .args(RUSTC_COLOR_ARGS)
-                    .output()
-                    .expect("Failed to run 'cargo clean'");
-                Command::new("cargo")
-                    .args(&["clippy", "--manifest-path", CLIPPY_CARGO_TOML_PATH])
+                let mut command = Command::new("cargo");
+                command
+                    .arg("clippy")
+                    .arg("--manifest-path")
+                    .arg(&cargo_toml_path)
+                    .arg("--target-dir")
+                    .arg(&target_dir)
                     .args(RUSTC_COLOR_ARGS)
-                    .args(&["--", "-D", "warnings", "-D", "clippy::float_cmp"])
-                    .output()
-            },
+                    .args(["--", "-D", "warnings", "-D", "clippy::float_cmp"])
+                    .args(self.rustc_flags());
+                command
+            }
             Mode::BuildScript => {
-                let cargo_toml = format!(
-                    r#"[package]
-name = "{}"
-version = "0.0.1"
-edition = "2021"
-[[bin]]
-name = "{}"
-path = "{}.rs""#,
-                    self.name, self.name, self.name
-                );
-                let cargo_toml_error_msg = if env::var("NO_EMOJI").is_ok() {
-                    "Failed to write Clippy Cargo.toml file."
-                } else {
-                    "Failed to write 📎 Clippy 📎 Cargo.toml file."
-                };
-                fs::write(BUILD_SCRIPT_CARGO_TOML_PATH, cargo_toml).expect(cargo_toml_error_msg);
-
-                Command::new("cargo")
-                    .args(&["test", "--manifest-path", BUILD_SCRIPT_CARGO_TOML_PATH])
-                    .output()
+                let cargo_toml_path = self
+                    .write_cargo_toml(&format!("build = {}\n", toml_path(&self.build_script())));
+
+                let mut command = Command::new("cargo");
+                command
+                    .arg("test")
+                    .arg("--manifest-path")
+                    .arg(&cargo_toml_path)
+                    .arg("--target-dir")
+                    .arg(&target_dir);
+                self.set_rustflags(&mut command);
+                command
             }
+            Mode::Cargo => {
+                self.update_lock_file();
+                match self.cargo_command() {
+                    CargoCommand::Test => self.cargo(&["test", "--no-run"]),
+                    CargoCommand::Run => self.cargo(&["build"]),
+                    CargoCommand::Clippy => {
+                        let mut command = self.cargo(&["clippy"]);
+                        command.args(["--", "-D", "warnings"]);
+                        command
+                    }
+                }
+            }
+        };
+        // Cargo runs build scripts, which are the learner's code as well
+        if let Mode::BuildScript | Mode::Cargo = self.mode {
+            self.sandbox(self.timeout())
+                .confine(&mut command)
+                .expect("Failed to set up the sandbox");
         }
-        .expect("Failed to run 'compile' command.");
+        let cmd = output_with_timeout(&mut command, self.timeout())
+            .expect("Failed to run 'compile' command.");
 
-        if cmd.status.success() {
+        if cmd.success() {
             Ok(CompiledExercise {
                 exercise: self,
-                _handle: FileHandle,
+                _handle: FileHandle { scratch_dir },
             })
         } else {
-            clean();
+            clean(&scratch_dir);
+            let stderr = String::from_utf8_lossy(&cmd.stderr).to_string();
+            let (diagnostics, stderr) = match self.mode {
+                Mode::Compile | Mode::Test => diagnostics::parse(&stderr),
+                Mode::Clippy | Mode::BuildScript | Mode::Cargo => (Vec::new(), stderr),
+            };
             Err(ExerciseOutput {
                 stdout: String::from_utf8_lossy(&cmd.stdout).to_string(),
-                stderr: String::from_utf8_lossy(&cmd.stderr).to_string(),
+                stderr,
+                timed_out: cmd.timed_out(),
+                diagnostics,
+                unexpected_output: false,
+                miri: MiriOutcome::NotRun,
             })
         }
     }
 
     fn run(&self) -> Result<ExerciseOutput, ExerciseOutput> {
-        let arg = match self.mode {
-            Mode::Test => "--show-output",
-            Mode::BuildScript => return Ok(ExerciseOutput {
-                stdout: "".to_string(),
-                stderr: "".to_string(),
-            }),
-            _ => "",
+        let mut command = match self.mode {
+            Mode::Cargo => match self.cargo_command() {
+                CargoCommand::Test => self.cargo(&["test"]),
+                CargoCommand::Run | CargoCommand::Clippy => self.cargo(&["run", "--quiet"]),
+            },
+            _ => Command::new(self.temp_file()),
         };
-        println!("pa={}", temp_file());
-        let cmd = Command::new(&temp_file())
-            .arg(arg)
-            .output()
+        match self.mode {
+            Mode::Test => {
+                command.arg("--show-output");
+            }
+            Mode::Cargo if self.cargo_command() == CargoCommand::Test => {
+                command.args(["--", "--show-output"]);
+            }
+            Mode::BuildScript => {
+                return Ok(ExerciseOutput {
+                    stdout: "".to_string(),
+                    stderr: "".to_string(),
+                    timed_out: false,
+                    diagnostics: Vec::new(),
+                    unexpected_output: false,
+                    miri: MiriOutcome::NotRun,
+                })
+            }
+            _ => {}
+        }
+        self.sandbox(self.timeout())
+            .confine(&mut command)
+            .expect("Failed to set up the sandbox");
+        let cmd = output_with_input(&mut command, self.stdin.as_deref(), self.timeout())
             .expect("Failed to run 'run' command");
 
-        let output = ExerciseOutput {
-            stdout: String::from_utf8_lossy(&cmd.stdout).to_string(),
+        let stdout = String::from_utf8_lossy(&cmd.stdout).to_string();
+        let unexpected_output = match (&self.expected_output, self.mode) {
+            (Some(expected), Mode::Compile) => cmd.success() && !expected.matches(&stdout),
+            (Some(expected), Mode::Cargo) if self.cargo_command() != CargoCommand::Test => {
+                cmd.success() && !expected.matches(&stdout)
+            }
+            _ => false,
+        };
+        let mut output = ExerciseOutput {
+            stdout,
             stderr: String::from_utf8_lossy(&cmd.stderr).to_string(),
+            timed_out: cmd.timed_out(),
+            diagnostics: Vec::new(),
+            unexpected_output,
+            miri: MiriOutcome::NotRun,
         };
 
-        if cmd.status.success() {
-            Ok(output)
-        } else {
-            Err(output)
+        if !cmd.success() || unexpected_output {
+            return Err(output);
+        }
+        if self.miri {
+            self.run_miri(&mut output);
+            if output.miri == MiriOutcome::Failed {
+                return Err(output);
+            }
         }
+        Ok(output)
     }
 
-    pub fn state(&self) -> State {
-        let mut source_file =
-            File::open(&self.path).expect("We were unable to open the exercise file!");
-
-        let source = {
-            let mut s = String::new();
-            source_file
-                .read_to_string(&mut s)
-                .expect("We were unable to read the exercise file!");
-            s
+    // Run the tests again under Miri. Passing tests don't prove that code
+    // with raw pointers is free of undefined behavior or leaks, but Miri
+    // reports both as errors. Miri needs a nightly toolchain, which doesn't
+    // have to be the default one.
+    fn run_miri(&self, output: &mut ExerciseOutput) {
+        if !miri_available() {
+            output.miri = MiriOutcome::Unavailable;
+            return;
+        }
+        let mut command = match self.mode {
+            Mode::Cargo => self.cargo(&["+nightly", "miri", "test"]),
+            _ => {
+                let cargo_toml_path = self.write_cargo_toml("");
+                let mut command = Command::new("cargo");
+                command
+                    .args(["+nightly", "miri", "test", "--manifest-path"])
+                    .arg(&cargo_toml_path)
+                    .arg("--target-dir")
+                    .arg(self.scratch_dir().join("target"))
+                    .args(RUSTC_COLOR_ARGS);
+                self.set_rustflags(&mut command);
+                command
+            }
         };
+        let timeout = self.timeout() * MIRI_SLOWDOWN;
+        self.sandbox(timeout)
+            .confine(&mut command)
+            .expect("Failed to set up the sandbox");
+        let cmd =
+            output_with_timeout(&mut command, timeout).expect("Failed to run 'cargo miri test'");
+        let reported = format!(
+            "{}{}",
+            String::from_utf8_lossy(&cmd.stdout),
+            String::from_utf8_lossy(&cmd.stderr)
+        );
+        output.miri = miri_outcome(cmd.success(), &reported);
+        if output.miri == MiriOutcome::Failed {
+            output.stderr = reported;
+        }
+    }
 
+    pub fn state(&self) -> State {
         let re = Regex::new(I_AM_DONE_REGEX).unwrap();
 
-        if !re.is_match(&source) {
+        // An exercise that is a directory is pending as long as any of its
+        // source files has the marker
+        let source = self
+            .files()
+            .iter()
+            .filter(|file| file.extension().is_some_and(|ext| ext == "rs"))
+            .map(|file| {
+                let mut source_file =
+                    File::open(file).expect("We were unable to open the exercise file!");
+                let mut s = String::new();
+                source_file
+                    .read_to_string(&mut s)
+                    .expect("We were unable to read the exercise file!");
+                s
+            })
+            .find(|source| re.is_match(source));
+        let Some(source) = source else {
             return State::Done;
-        }
+        };
 
         let matched_line_index = source
             .lines()
@@ -270,16 +885,6 @@ path = "{}.rs""#,
 
         State::Pending(context)
     }
-
-    // Check that the exercise looks to be solved using self.state()
-    // This is not the best way to check since
-    // the user can just remove the "I AM NOT DONE" string from the file
-    // without actually having solved anything.
-    // The only other way to truly check this would to compile and run
-    // the exercise; which would be both costly and counterintuitive
-    pub fn looks_done(&self) -> bool {
-        self.state() == State::Done
-    }
 }
 
 impl Display for Exercise {
@@ -288,28 +893,128 @@ impl Display for Exercise {
     }
 }
 
-#[inline]
-fn clean() {
-    let _ignored = remove_file(&temp_file());
+// Remove an exercise's scratch directory, and the scratch root too unless
+// a `ScratchRoot` keeps it for other exercises
+fn clean(scratch_dir: &Path) {
+    let _ignored = fs::remove_dir_all(scratch_dir);
+    if SCRATCH_ROOT_HOLDERS.load(Ordering::SeqCst) == 0 {
+        let _ignored = fs::remove_dir(scratch_root());
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use std::path::Path;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_hints() {
+        let exercise = Exercise {
+            hint: Localized::Text(String::from("first")),
+            hints: vec![
+                Localized::Text(String::new()),
+                Localized::PerLocale(BTreeMap::from([(String::from("zh"), String::from("第二"))])),
+                Localized::Text(String::from("  \n")),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(exercise.hints(), ["first", "第二"]);
+        let exercise = Exercise {
+            hint: Localized::default(),
+            ..exercise
+        };
+        assert_eq!(exercise.hints(), ["第二"]);
+    }
 
     #[test]
     fn test_clean() {
-        File::create(&temp_file()).unwrap();
         let exercise = Exercise {
             name: String::from("example"),
             path: PathBuf::from("tests/fixture/state/pending_exercise.rs"),
             mode: Mode::Compile,
-            hint: String::from(""),
+            ..Default::default()
         };
         let compiled = exercise.compile().unwrap();
+        assert!(exercise.temp_file().exists());
         drop(compiled);
-        assert!(!Path::new(&temp_file()).exists());
+        assert!(!exercise.scratch_dir().exists());
+    }
+
+    #[test]
+    fn test_parallel_compiles_are_isolated() {
+        let exercises: Vec<Exercise> = ["parallel_success", "parallel_failure"]
+            .iter()
+            .zip(["tests/fixture/success/testSuccess.rs", "tests/fixture/failure/testNotPassed.rs"])
+            .map(|(name, path)| Exercise {
+                name: name.to_string(),
+                path: PathBuf::from(path),
+                mode: Mode::Test,
+                ..Default::default()
+            })
+            .collect();
+        let root = ScratchRoot::create().unwrap();
+        let results: Vec<bool> = std::thread::scope(|s| {
+            let handles: Vec<_> = exercises
+                .iter()
+                .map(|e| s.spawn(move || e.compile().unwrap().run().is_ok()))
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+        assert_eq!(results, vec![true, false]);
+        // Neither exercise removed the root the other one might still use
+        assert!(scratch_root().exists());
+        drop(root);
+    }
+
+    #[test]
+    fn test_run_timeout() {
+        let exercise = Exercise {
+            name: "run_timeout".into(),
+            path: PathBuf::from("tests/fixture/timeout/compLoop.rs"),
+            mode: Mode::Compile,
+            timeout: Some(1),
+            ..Default::default()
+        };
+        let out = exercise.compile().unwrap().run().unwrap_err();
+        assert!(out.timed_out);
+        assert_eq!(exercise.sandbox(exercise.timeout()).cpu_secs, 2);
+        let exercise = Exercise {
+            timeout: Some(0),
+            ..exercise
+        };
+        assert_eq!(exercise.sandbox(exercise.timeout()).cpu_secs, 2);
+    }
+
+    #[test]
+    fn test_miri_outcome() {
+        assert_eq!(miri_outcome(true, ""), MiriOutcome::Passed);
+        let leak =
+            "\x1b[1m\x1b[91merror\x1b[0m: memory leaked: alloc1 (Rust heap, size: 4, align: 4)";
+        assert_eq!(miri_outcome(false, leak), MiriOutcome::Failed);
+        let ub = "error: Undefined Behavior: pointer not dereferenceable";
+        assert_eq!(miri_outcome(false, ub), MiriOutcome::Failed);
+        let unsupported =
+            "error: unsupported operation: can't call foreign function `posix_spawnp`";
+        assert_eq!(miri_outcome(false, unsupported), MiriOutcome::Unavailable);
+        assert_eq!(
+            miri_outcome(false, "error: failed to build the sysroot"),
+            MiriOutcome::Unavailable
+        );
+    }
+
+    #[test]
+    fn test_category() {
+        let mut exercise = Exercise {
+            name: "quiz1".into(),
+            path: PathBuf::from("exercises/quiz1.rs"),
+            mode: Mode::Test,
+            ..Default::default()
+        };
+        assert_eq!(exercise.category(), "quiz");
+        exercise.path = PathBuf::from("exercises/algorithm/algorithm1.rs");
+        assert_eq!(exercise.category(), "algorithm");
+        exercise.category = Some("data structures".into());
+        assert_eq!(exercise.category(), "data structures");
     }
 
     #[test]
@@ -318,7 +1023,7 @@ mod test {
             name: "pending_exercise".into(),
             path: PathBuf::from("tests/fixture/state/pending_exercise.rs"),
             mode: Mode::Compile,
-            hint: String::new(),
+            ..Default::default()
         };
 
         let state = exercise.state();
@@ -359,7 +1064,7 @@ mod test {
             name: "finished_exercise".into(),
             path: PathBuf::from("tests/fixture/state/finished_exercise.rs"),
             mode: Mode::Compile,
-            hint: String::new(),
+            ..Default::default()
         };
 
         assert_eq!(exercise.state(), State::Done);
@@ -371,7 +1076,7 @@ mod test {
             name: "exercise_with_output".into(),
             path: PathBuf::from("tests/fixture/success/testSuccess.rs"),
             mode: Mode::Test,
-            hint: String::new(),
+            ..Default::default()
         };
         let out = exercise.compile().unwrap().run().unwrap();
         assert!(out.stdout.contains("THIS TEST TOO SHALL PASS"));
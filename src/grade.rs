@@ -0,0 +1,415 @@
+use crate::exercise::{
+    Attempt, CargoCommand, Exercise, ExerciseOutput, MiriOutcome, Mode, ScratchRoot,
+};
+use crate::harness::{self, TestCase};
+use crate::i18n;
+use crate::progress::Progress;
+use crate::tamper;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+
+// Captured diagnostics and output are cut off after this many bytes
+const MAX_CAPTURED_OUTPUT: usize = 8 * 1024;
+
+#[derive(Deserialize, Serialize)]
+pub struct ExerciseCheckList {
+    pub exercises: Vec<ExerciseResult>,
+    pub user_name: Option<String>,
+    pub statistics: ExerciseStatistics,
+    // The output of `rustc --version` for the toolchain that graded the exercises
+    pub rustc_version: Option<String>,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct ExerciseResult {
+    pub name: String,
+    pub result: bool,
+    pub category: String,
+    // The points the exercise is worth and the points it scored
+    pub points: u32,
+    pub earned_points: f64,
+    pub mode: Mode,
+    pub path: PathBuf,
+    // The phase the exercise failed in, if it failed
+    pub phase: Option<Phase>,
+    pub duration_ms: u64,
+    // What the compiler (or Clippy) had to say, if the exercise didn't build
+    pub diagnostics: Option<String>,
+    // The output of the exercise or its tests, if it got to run
+    pub output: Option<String>,
+    // The individual tests of a test exercise
+    #[serde(default)]
+    pub tests: Vec<TestCase>,
+    // How many hints the learner revealed
+    #[serde(default)]
+    pub hints_revealed: usize,
+    // Whether the exercise's tests differ from the ones it was shipped with
+    #[serde(default)]
+    pub tampered: bool,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct ExerciseStatistics {
+    pub total_exercations: usize,
+    pub total_succeeds: usize,
+    pub total_failures: usize,
+    pub total_time: u32,
+    pub total_time_ms: u64,
+    pub total_points: u32,
+    pub earned_points: f64,
+    #[serde(default)]
+    pub total_hints_revealed: usize,
+    pub categories: BTreeMap<String, CategoryStatistics>,
+}
+
+impl ExerciseStatistics {
+    // The share of the points that were earned, from 0 to 100
+    pub fn percentage(&self) -> f64 {
+        match self.total_points {
+            0 => 100.0,
+            total => self.earned_points / f64::from(total) * 100.0,
+        }
+    }
+}
+
+// The subtotals of the exercises in one category
+#[derive(Deserialize, Serialize, Default)]
+pub struct CategoryStatistics {
+    pub total_exercations: usize,
+    pub total_succeeds: usize,
+    pub total_points: u32,
+    pub earned_points: f64,
+}
+
+// The step of grading an exercise that failed
+#[derive(Deserialize, Serialize, PartialEq, Clone, Copy, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Phase {
+    // The exercise didn't compile
+    Compile,
+    // Clippy rejected the exercise
+    Clippy,
+    // The exercise compiled, but its tests failed
+    Test,
+    // The exercise compiled, but exited with an error
+    Run,
+    // The exercise ran, but didn't print its expected output
+    Output,
+    // The tests passed, but Miri found undefined behavior or a leak
+    Miri,
+    // The tests of the exercise were changed, so it wasn't run
+    Tampered,
+    // Compiling or running the exercise took too long
+    Timeout,
+}
+
+// The number of exercises graded at once when `--jobs` isn't given
+pub fn default_jobs() -> usize {
+    std::thread::available_parallelism()
+        .map(usize::from)
+        .unwrap_or(1)
+}
+
+// Grade every exercise, running up to `jobs` of them at the same time.
+// Each exercise compiles in its own scratch directory, and the results are
+// reported in the order of `info.toml` no matter which exercise finishes first.
+// Unless `quiet` is set, a line is printed as each exercise is graded.
+pub async fn grade(
+    exercises: Vec<Exercise>,
+    jobs: usize,
+    quiet: bool,
+    progress: &Progress,
+) -> ExerciseCheckList {
+    let start = Instant::now();
+    let total = exercises.len();
+    let succeeds = Arc::new(AtomicUsize::new(0));
+    let semaphore = Arc::new(Semaphore::new(jobs.max(1)));
+    // Created once for all the tasks, and removed after they've all joined
+    let scratch_root = ScratchRoot::create().expect("Failed to create the scratch directory");
+
+    let mut tasks = Vec::with_capacity(total);
+    for exercise in exercises {
+        let permit = Arc::clone(&semaphore)
+            .acquire_owned()
+            .await
+            .expect("The grading semaphore is never closed");
+        let succeeds = Arc::clone(&succeeds);
+        let hints_revealed = progress.hints_revealed(&exercise);
+        tasks.push(tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            let result = ExerciseResult {
+                hints_revealed,
+                ..check(&exercise)
+            };
+            let done = if result.result {
+                succeeds.fetch_add(1, Ordering::SeqCst) + 1
+            } else {
+                succeeds.load(Ordering::SeqCst)
+            };
+
+            if !quiet {
+                let seconds = format!("{:.1}", result.duration_ms as f64 / 1000.0);
+                let key = match result.phase {
+                    None => "grade.passed",
+                    Some(Phase::Timeout) => "grade.timed_out",
+                    Some(Phase::Tampered) => "grade.tampered",
+                    Some(_) => "grade.failed",
+                };
+                let line = i18n::fill(
+                    i18n::text(key),
+                    &[
+                        ("exercise", exercise.name.clone()),
+                        ("seconds", seconds),
+                        ("done", done.to_string()),
+                        ("total", total.to_string()),
+                    ],
+                );
+                println!("{line}");
+            }
+
+            result
+        }));
+    }
+
+    let mut results = Vec::with_capacity(total);
+    for task in tasks {
+        results.push(task.await.expect("Grading an exercise panicked"));
+    }
+    drop(scratch_root);
+    let check_list = check_list(results, start.elapsed());
+    if !quiet {
+        let statistics = &check_list.statistics;
+        println!(
+            "{}",
+            t!(
+                "grade.summary",
+                total = total,
+                seconds = statistics.total_time,
+                passed = statistics.total_succeeds,
+                earned = statistics.earned_points,
+                points = statistics.total_points,
+                percentage = format!("{:.1}", statistics.percentage())
+            )
+        );
+    }
+    check_list
+}
+
+// Total up the results of the exercises that were graded
+pub fn check_list(results: Vec<ExerciseResult>, total_time: Duration) -> ExerciseCheckList {
+    let total = results.len();
+    let total_succeeds = results.iter().filter(|r| r.result).count();
+    let mut categories: BTreeMap<String, CategoryStatistics> = BTreeMap::new();
+    for result in &results {
+        let category = categories.entry(result.category.clone()).or_default();
+        category.total_exercations += 1;
+        category.total_succeeds += usize::from(result.result);
+        category.total_points += result.points;
+        category.earned_points += result.earned_points;
+    }
+
+    let statistics = ExerciseStatistics {
+        total_exercations: total,
+        total_succeeds,
+        total_failures: total - total_succeeds,
+        total_time: total_time.as_secs() as u32,
+        total_time_ms: total_time.as_millis() as u64,
+        total_points: results.iter().map(|r| r.points).sum(),
+        earned_points: results.iter().map(|r| r.earned_points).sum(),
+        total_hints_revealed: results.iter().map(|r| r.hints_revealed).sum(),
+        categories,
+    };
+
+    ExerciseCheckList {
+        exercises: results,
+        user_name: None,
+        statistics,
+        rustc_version: rustc_version(),
+    }
+}
+
+// Compile and run a single exercise without printing anything
+pub fn check(exercise: &Exercise) -> ExerciseResult {
+    let start = Instant::now();
+    if tamper::tampered(exercise) {
+        return tampered(exercise, start.elapsed());
+    }
+    let attempt = exercise.attempt();
+    graded(exercise, &attempt, start.elapsed())
+}
+
+// The result of an attempt that was already made, like the one of the
+// exercise `rustlings verify` stopped at
+pub fn result(exercise: &Exercise, attempt: &Attempt, duration: Duration) -> ExerciseResult {
+    if tamper::tampered(exercise) {
+        return tampered(exercise, duration);
+    }
+    graded(exercise, attempt, duration)
+}
+
+fn graded(exercise: &Exercise, attempt: &Attempt, duration: Duration) -> ExerciseResult {
+    let mut tests = Vec::new();
+    let mut partial_credit = None;
+    let (phase, diagnostics, output) = match attempt {
+        Attempt::Cached(_) => (None, None, None),
+        Attempt::CompileFailed(output) => {
+            let phase = match exercise.mode {
+                _ if output.timed_out => Phase::Timeout,
+                Mode::Compile | Mode::Test => Phase::Compile,
+                Mode::Clippy => Phase::Clippy,
+                Mode::Cargo => match exercise.cargo_command() {
+                    CargoCommand::Test | CargoCommand::Run => Phase::Compile,
+                    CargoCommand::Clippy => Phase::Clippy,
+                },
+                // `cargo test` builds and runs the tests in one go
+                Mode::BuildScript => Phase::Test,
+            };
+            (
+                Some(phase),
+                Some(truncate(&output.stderr)),
+                non_empty(&output.stdout),
+            )
+        }
+        Attempt::Ran(Ok(output)) => {
+            if runs_tests(exercise) {
+                tests = harness::parse(&output.stdout);
+            }
+            (None, None, Some(captured(output)))
+        }
+        Attempt::Ran(Err(output)) => {
+            // Every passing test of a harness that ran to the end counts,
+            // unless they all passed and Miri failed the exercise
+            if runs_tests(exercise) && !output.timed_out && output.miri != MiriOutcome::Failed {
+                tests = harness::parse(&output.stdout);
+                partial_credit = harness::partial_credit(&output.stdout);
+            }
+            let phase = match exercise.mode {
+                _ if output.timed_out => Phase::Timeout,
+                _ if output.unexpected_output => Phase::Output,
+                _ if output.miri == MiriOutcome::Failed => Phase::Miri,
+                Mode::Test | Mode::BuildScript => Phase::Test,
+                Mode::Compile | Mode::Clippy => Phase::Run,
+                Mode::Cargo => match exercise.cargo_command() {
+                    CargoCommand::Test => Phase::Test,
+                    CargoCommand::Run | CargoCommand::Clippy => Phase::Run,
+                },
+            };
+            (Some(phase), None, Some(captured(output)))
+        }
+    };
+
+    let points = exercise.points();
+    ExerciseResult {
+        result: phase.is_none(),
+        earned_points: match phase {
+            None => points.into(),
+            Some(_) => partial_credit.unwrap_or(0.0) * f64::from(points),
+        },
+        phase,
+        duration_ms: duration.as_millis() as u64,
+        diagnostics,
+        output,
+        tests,
+        ..passed(exercise)
+    }
+}
+
+fn tampered(exercise: &Exercise, duration: Duration) -> ExerciseResult {
+    let diagnostics = t!("grade.tests_changed");
+    ExerciseResult {
+        result: false,
+        earned_points: 0.0,
+        phase: Some(Phase::Tampered),
+        duration_ms: duration.as_millis() as u64,
+        diagnostics: Some(diagnostics),
+        tampered: true,
+        ..passed(exercise)
+    }
+}
+
+// The result of an exercise that passed without being graded again, like
+// one that `rustlings verify` took from the cache
+pub fn passed(exercise: &Exercise) -> ExerciseResult {
+    let points = exercise.points();
+    ExerciseResult {
+        name: exercise.name.clone(),
+        result: true,
+        category: exercise.category(),
+        points,
+        earned_points: points.into(),
+        mode: exercise.mode,
+        path: exercise.path.clone(),
+        phase: None,
+        duration_ms: 0,
+        diagnostics: None,
+        output: None,
+        tests: Vec::new(),
+        hints_revealed: 0,
+        tampered: false,
+    }
+}
+
+// Whether running the exercise runs a libtest harness, whose output lists
+// the individual tests
+fn runs_tests(exercise: &Exercise) -> bool {
+    match exercise.mode {
+        Mode::Test => true,
+        Mode::Cargo => exercise.cargo_command() == CargoCommand::Test,
+        Mode::Compile | Mode::Clippy | Mode::BuildScript => false,
+    }
+}
+
+// Everything an exercise printed, with stderr after stdout
+fn captured(output: &ExerciseOutput) -> String {
+    if output.stderr.is_empty() {
+        truncate(&output.stdout)
+    } else {
+        truncate(&format!("{}\n{}", output.stdout, output.stderr))
+    }
+}
+
+fn non_empty(text: &str) -> Option<String> {
+    (!text.trim().is_empty()).then(|| truncate(text))
+}
+
+// Strip the terminal colors from captured text and cap its length
+fn truncate(text: &str) -> String {
+    let mut text = console::strip_ansi_codes(text).into_owned();
+    if text.len() > MAX_CAPTURED_OUTPUT {
+        let mut end = MAX_CAPTURED_OUTPUT;
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        text.truncate(end);
+        text.push_str("\n... (truncated)");
+    }
+    text
+}
+
+fn rustc_version() -> Option<String> {
+    let output = Command::new("rustc").arg("--version").output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate("\x1b[31mred\x1b[0m"), "red");
+        let long = "é".repeat(MAX_CAPTURED_OUTPUT);
+        let truncated = truncate(&long);
+        assert!(truncated.ends_with("... (truncated)"));
+        assert!(truncated.len() <= MAX_CAPTURED_OUTPUT + "\n... (truncated)".len());
+    }
+}
@@ -0,0 +1,167 @@
+use crate::exercise::Exercise;
+use crate::progress::Progress;
+use std::collections::{HashMap, HashSet};
+
+// Check that the prerequisites of the exercises name existing exercises
+// and that no exercise requires itself, directly or through others
+pub fn validate(exercises: &[Exercise]) -> Result<(), String> {
+    let index: HashMap<&str, usize> = exercises
+        .iter()
+        .enumerate()
+        .map(|(i, e)| (e.name.as_str(), i))
+        .collect();
+    for exercise in exercises {
+        if let Some(unknown) = exercise
+            .requires
+            .iter()
+            .find(|r| !index.contains_key(r.as_str()))
+        {
+            return Err(t!(
+                "graph.unknown_prerequisite",
+                exercise = exercise.name,
+                prerequisite = unknown
+            ));
+        }
+    }
+
+    // Depth first search, keeping the path to the current exercise so that
+    // a cycle can be reported
+    let mut finished = vec![false; exercises.len()];
+    let mut path = Vec::new();
+    for start in 0..exercises.len() {
+        if let Some(cycle) = find_cycle(start, exercises, &index, &mut finished, &mut path) {
+            return Err(t!("graph.cycle", cycle = cycle));
+        }
+    }
+    Ok(())
+}
+
+fn find_cycle(
+    i: usize,
+    exercises: &[Exercise],
+    index: &HashMap<&str, usize>,
+    finished: &mut [bool],
+    path: &mut Vec<usize>,
+) -> Option<String> {
+    if finished[i] {
+        return None;
+    }
+    if let Some(start) = path.iter().position(|&p| p == i) {
+        let names: Vec<&str> = path[start..]
+            .iter()
+            .chain([&i])
+            .map(|&p| exercises[p].name.as_str())
+            .collect();
+        return Some(names.join(" -> "));
+    }
+    path.push(i);
+    for required in &exercises[i].requires {
+        if let Some(cycle) = find_cycle(index[required.as_str()], exercises, index, finished, path)
+        {
+            return Some(cycle);
+        }
+    }
+    path.pop();
+    finished[i] = true;
+    None
+}
+
+// The exercises in the order of info.toml, except that every exercise
+// comes after its prerequisites. The prerequisites must have been validated.
+pub fn order(exercises: &[Exercise]) -> Vec<&Exercise> {
+    let mut placed: HashSet<&str> = HashSet::new();
+    let mut remaining: Vec<&Exercise> = exercises.iter().collect();
+    let mut ordered = Vec::with_capacity(exercises.len());
+    while !remaining.is_empty() {
+        let next = remaining
+            .iter()
+            .position(|e| e.requires.iter().all(|r| placed.contains(r.as_str())))
+            .expect("The prerequisites of the exercises form a cycle");
+        let exercise = remaining.remove(next);
+        placed.insert(&exercise.name);
+        ordered.push(exercise);
+    }
+    ordered
+}
+
+// The prerequisites of an exercise that aren't done yet. The exercise is
+// locked until there are none.
+pub fn locked_by<'a>(
+    exercise: &'a Exercise,
+    exercises: &[Exercise],
+    progress: &Progress,
+) -> Vec<&'a str> {
+    exercise
+        .requires
+        .iter()
+        .filter(|r| {
+            !exercises
+                .iter()
+                .any(|e| &e.name == *r && progress.is_done(e))
+        })
+        .map(String::as_str)
+        .collect()
+}
+
+// The first exercise that isn't done yet and whose prerequisites are
+pub fn next<'a>(exercises: &'a [Exercise], progress: &Progress) -> Option<&'a Exercise> {
+    exercises
+        .iter()
+        .find(|e| !progress.is_done(e) && locked_by(e, exercises, progress).is_empty())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::exercise::Mode;
+    use std::path::PathBuf;
+
+    fn exercise(name: &str, requires: &[&str]) -> Exercise {
+        Exercise {
+            name: name.to_string(),
+            path: PathBuf::from(format!("{name}.rs")),
+            mode: Mode::Compile,
+            requires: requires.iter().map(|r| r.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        let exercises = [
+            exercise("a", &[]),
+            exercise("b", &["a"]),
+            exercise("c", &["a", "b"]),
+        ];
+        assert_eq!(validate(&exercises), Ok(()));
+
+        let unknown = [exercise("a", &["z"])];
+        assert_eq!(
+            validate(&unknown),
+            Err(String::from("a requires `z`, which is not an exercise"))
+        );
+
+        let cycle = [
+            exercise("a", &["c"]),
+            exercise("b", &["a"]),
+            exercise("c", &["b"]),
+        ];
+        assert_eq!(
+            validate(&cycle),
+            Err(String::from(
+                "the prerequisites form a cycle: a -> c -> b -> a"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_order() {
+        let exercises = [
+            exercise("a", &["c"]),
+            exercise("b", &[]),
+            exercise("c", &[]),
+        ];
+        let names: Vec<&str> = order(&exercises).iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["b", "c", "a"]);
+    }
+}
@@ -0,0 +1,248 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+// The outcome of a single `#[test]`
+#[derive(Deserialize, Serialize, PartialEq, Clone, Copy, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum TestStatus {
+    Passed,
+    Failed,
+    Ignored,
+}
+
+// A single `#[test]` as reported by a test harness
+#[derive(Deserialize, Serialize, PartialEq, Debug)]
+pub struct TestCase {
+    // The path of the test function, like `tests::it_works`
+    pub name: String,
+    pub status: TestStatus,
+    // What the test panicked with, if it failed
+    pub message: Option<String>,
+}
+
+// Where a line of a libtest harness's output is
+#[derive(PartialEq)]
+enum Section {
+    // Before a harness starts, or after it printed its summary
+    Outside,
+    // Among the status lines of the tests
+    Status,
+    // In the captured output of the tests, which is the tests' own text
+    Output,
+}
+
+// The tests of every harness in the output, and whether each harness's
+// status lines agree with the summary it printed at the end. Only the
+// lines before the `successes:` and `failures:` sections are read, as
+// the captured output in those sections is up to the tests.
+fn read(stdout: &str) -> (Vec<TestCase>, bool) {
+    let running_re = Regex::new(r"^running \d+ tests?$").unwrap();
+    let line_re =
+        Regex::new(r"^test (.+?)(?: - should panic)? \.\.\. (ok|FAILED|ignored)").unwrap();
+    let summary_re =
+        Regex::new(r"^test result: \w+\. (\d+) passed; (\d+) failed; (\d+) ignored;").unwrap();
+    let mut tests = Vec::new();
+    let mut consistent = true;
+    let mut summaries = 0;
+    let mut section = Section::Outside;
+    let mut start = 0;
+    for line in stdout.lines() {
+        if running_re.is_match(line) && section == Section::Outside {
+            section = Section::Status;
+            start = tests.len();
+        } else if line == "successes:" || line == "failures:" {
+            consistent &= section != Section::Outside;
+            section = Section::Output;
+        } else if let Some(caps) = summary_re.captures(line) {
+            let block: &[TestCase] = &tests[start..];
+            let count = |status| block.iter().filter(|t| t.status == status).count();
+            let counts = [TestStatus::Passed, TestStatus::Failed, TestStatus::Ignored].map(count);
+            consistent &= section != Section::Outside
+                && (1..=3).all(|i| caps[i].parse() == Ok(counts[i - 1]));
+            summaries += 1;
+            section = Section::Outside;
+        } else if section == Section::Status {
+            let Some(caps) = line_re.captures(line) else {
+                continue;
+            };
+            // libtest reports every test once
+            if tests[start..].iter().any(|t: &TestCase| t.name == caps[1]) {
+                consistent = false;
+                continue;
+            }
+            tests.push(TestCase {
+                name: caps[1].to_string(),
+                status: match &caps[2] {
+                    "ok" => TestStatus::Passed,
+                    "FAILED" => TestStatus::Failed,
+                    _ => TestStatus::Ignored,
+                },
+                message: None,
+            });
+        }
+    }
+    (
+        tests,
+        consistent && summaries > 0 && section == Section::Outside,
+    )
+}
+
+// Read the individual tests out of the standard output of a libtest harness.
+// The line format has been stable for years, unlike `--format json`, which
+// still requires a nightly toolchain.
+pub fn parse(stdout: &str) -> Vec<TestCase> {
+    let (mut tests, _) = read(stdout);
+
+    // The captured output of every failed test follows its own header in
+    // the `failures:` section
+    let header_re = Regex::new(r"^---- (.+?)(?: - should panic)? stdout ----$").unwrap();
+    let mut current: Option<(String, Vec<&str>)> = None;
+    let mut sections = Vec::new();
+    for line in stdout.lines() {
+        if let Some(caps) = header_re.captures(line) {
+            sections.extend(current.take());
+            current = Some((caps[1].to_string(), Vec::new()));
+        } else if line == "failures:" || line == "successes:" {
+            sections.extend(current.take());
+        } else if let Some((_, lines)) = &mut current {
+            lines.push(line);
+        }
+    }
+    sections.extend(current);
+
+    for (name, lines) in sections {
+        if let Some(test) = tests
+            .iter_mut()
+            .find(|t| t.name == name && t.status == TestStatus::Failed)
+        {
+            test.message = panic_message(&lines);
+        }
+    }
+    tests
+}
+
+// Pick the panic message out of a failed test's output, dropping the
+// `thread '...' panicked at` line and the backtrace
+fn panic_message(lines: &[&str]) -> Option<String> {
+    let start = lines
+        .iter()
+        .position(|l| l.starts_with("thread '") && l.contains("panicked at"))
+        .map_or(0, |i| i + 1);
+    let message: Vec<&str> = lines[start..]
+        .iter()
+        .take_while(|l| !l.starts_with("note: ") && **l != "stack backtrace:")
+        .copied()
+        .collect();
+    let message = message.join("\n").trim().to_string();
+    (!message.is_empty()).then_some(message)
+}
+
+// The share of the tests that passed, not counting ignored ones. There is
+// none if a harness crashed halfway, or if its status lines don't add up to
+// its summary, which happens when a test prints lines that look like them.
+pub fn partial_credit(stdout: &str) -> Option<f64> {
+    match read(stdout) {
+        (tests, true) => pass_ratio(&tests),
+        (_, false) => None,
+    }
+}
+
+fn pass_ratio(tests: &[TestCase]) -> Option<f64> {
+    let counted = tests
+        .iter()
+        .filter(|t| t.status != TestStatus::Ignored)
+        .count();
+    let passed = tests
+        .iter()
+        .filter(|t| t.status == TestStatus::Passed)
+        .count();
+    (counted > 0).then(|| passed as f64 / counted as f64)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const OUTPUT: &str = r#"
+running 4 tests
+test tests::ignored ... ignored, not yet
+test tests::passing ... ok
+test tests::panics - should panic ... ok
+test tests::failing ... FAILED
+
+successes:
+
+---- tests::passing stdout ----
+some output
+
+successes:
+    tests::passing
+
+failures:
+
+---- tests::failing stdout ----
+
+thread 'tests::failing' panicked at src/lib.rs:9:9:
+assertion `left == right` failed
+  left: 1
+ right: 2
+note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace
+
+
+failures:
+    tests::failing
+
+test result: FAILED. 2 passed; 1 failed; 1 ignored; 0 measured; 0 filtered out; finished in 0.00s
+"#;
+
+    #[test]
+    fn test_parse() {
+        let tests = parse(OUTPUT);
+        let statuses: Vec<(&str, TestStatus)> =
+            tests.iter().map(|t| (t.name.as_str(), t.status)).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                ("tests::ignored", TestStatus::Ignored),
+                ("tests::passing", TestStatus::Passed),
+                ("tests::panics", TestStatus::Passed),
+                ("tests::failing", TestStatus::Failed),
+            ]
+        );
+        assert_eq!(
+            tests[3].message.as_deref(),
+            Some("assertion `left == right` failed\n  left: 1\n right: 2")
+        );
+        assert_eq!(tests[1].message, None);
+        assert_eq!(partial_credit(OUTPUT), Some(2.0 / 3.0));
+        assert_eq!(
+            partial_credit("running 1 test\ntest tests::passing ... ok\n"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_faked_status_lines() {
+        // A failing test printing status lines in its captured output
+        let faked = OUTPUT.replace(
+            "\nthread 'tests::failing'",
+            "test tests::failing ... ok\ntest tests::fake ... ok\n\nthread 'tests::failing'",
+        );
+        assert_eq!(parse(&faked).len(), 4);
+        assert_eq!(partial_credit(&faked), Some(2.0 / 3.0));
+
+        // Or even a whole harness of its own
+        let faked = OUTPUT.replace(
+            "\nthread 'tests::failing'",
+            "test result: ok. 2 passed; 1 failed; 1 ignored;\n\nrunning 2 tests\ntest tests::fake ... ok\ntest tests::fake ... ok\n\nthread 'tests::failing'",
+        );
+        assert_eq!(partial_credit(&faked), None);
+
+        // Status lines that don't add up to the summary
+        let faked = OUTPUT.replace(
+            "test tests::ignored",
+            "test tests::fake ... ok\ntest tests::ignored",
+        );
+        assert_eq!(partial_credit(&faked), None);
+    }
+}
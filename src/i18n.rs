@@ -0,0 +1,272 @@
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::env;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+// The message catalogues, compiled into the binary
+const EN: &str = include_str!("../locales/en.toml");
+const ZH_CN: &str = include_str!("../locales/zh-CN.toml");
+
+// Look up a message in the catalogue of the current locale and fill in
+// its placeholders, like `t!("verify.ran", exercise = exercise)`
+macro_rules! t {
+    ($key:literal) => {
+        $crate::i18n::text($key).to_string()
+    };
+    ($key:literal, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::i18n::fill(
+            $crate::i18n::text($key),
+            &[$((stringify!($name), $value.to_string())),+],
+        )
+    };
+}
+
+// The languages rustlings speaks
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Locale {
+    En,
+    ZhCn,
+}
+
+impl FromStr for Locale {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Locale::from_env(s).ok_or_else(|| format!("unknown language `{s}`, expected en or zh-CN"))
+    }
+}
+
+impl Locale {
+    // Read a locale the way `LANG` spells it, like `zh_CN.UTF-8`
+    fn from_env(value: &str) -> Option<Locale> {
+        let language = value
+            .split(['.', '@'])
+            .next()
+            .unwrap_or_default()
+            .replace('_', "-")
+            .to_lowercase();
+        match language.split('-').next() {
+            Some("en") => Some(Locale::En),
+            Some("zh") => Some(Locale::ZhCn),
+            _ => None,
+        }
+    }
+
+    // The keys that text for this locale has in `info.toml`, best first
+    fn keys(self) -> &'static [&'static str] {
+        match self {
+            Locale::En => &INFO_LOCALES[..1],
+            Locale::ZhCn => &INFO_LOCALES[1..],
+        }
+    }
+
+    fn catalogue(self) -> &'static HashMap<String, String> {
+        static CATALOGUES: OnceLock<[HashMap<String, String>; 2]> = OnceLock::new();
+        let [en, zh_cn] = CATALOGUES.get_or_init(|| [parse(EN), parse(ZH_CN)]);
+        match self {
+            Locale::En => en,
+            Locale::ZhCn => zh_cn,
+        }
+    }
+}
+
+// The locales text in `info.toml` can be given in
+pub const INFO_LOCALES: &[&str] = &["en", "zh-CN", "zh"];
+
+static LOCALE: OnceLock<Locale> = OnceLock::new();
+
+// Choose the locale from `--lang`, or else from `RUSTLINGS_LANG` or `LANG`.
+// Anything that isn't a known language falls back to English.
+pub fn init(lang: Option<Locale>) {
+    let locale = lang
+        .or_else(|| {
+            env::var("RUSTLINGS_LANG")
+                .ok()
+                .and_then(|v| Locale::from_env(&v))
+        })
+        .or_else(|| env::var("LANG").ok().and_then(|v| Locale::from_env(&v)))
+        .unwrap_or(Locale::En);
+    let _ = LOCALE.set(locale);
+}
+
+pub fn locale() -> Locale {
+    LOCALE.get().copied().unwrap_or(Locale::En)
+}
+
+// The message for `key` in the current locale, in English if it isn't
+// translated, or the key itself if there is no such message
+pub fn text(key: &'static str) -> &'static str {
+    locale()
+        .catalogue()
+        .get(key)
+        .or_else(|| Locale::En.catalogue().get(key))
+        .map_or(key, String::as_str)
+}
+
+// Replace the `{name}` placeholders of a message
+pub fn fill(message: &str, values: &[(&str, String)]) -> String {
+    values
+        .iter()
+        .fold(message.to_string(), |message, (name, value)| {
+            message.replace(&format!("{{{name}}}"), value)
+        })
+}
+
+// Flatten a catalogue's tables into keys like `verify.ran`
+fn parse(catalogue: &str) -> HashMap<String, String> {
+    fn flatten(prefix: &str, table: toml::value::Table, messages: &mut HashMap<String, String>) {
+        for (key, value) in table {
+            let key = if prefix.is_empty() {
+                key
+            } else {
+                format!("{prefix}.{key}")
+            };
+            match value {
+                toml::Value::Table(table) => flatten(&key, table, messages),
+                toml::Value::String(message) => {
+                    messages.insert(key, message);
+                }
+                _ => {}
+            }
+        }
+    }
+    let table = toml::from_str(catalogue).expect("The message catalogues are valid TOML");
+    let mut messages = HashMap::new();
+    flatten("", table, &mut messages);
+    messages
+}
+
+// Text in `info.toml` that is either the same in every locale, or given
+// per locale, like `hint.en = "..."` and `hint.zh = "..."`
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum Localized {
+    Text(String),
+    PerLocale(BTreeMap<String, String>),
+}
+
+impl Default for Localized {
+    fn default() -> Self {
+        Localized::Text(String::new())
+    }
+}
+
+impl Localized {
+    // The text in the current locale, or else in English, or else in any
+    // locale that has some
+    pub fn get(&self) -> &str {
+        self.get_in(locale())
+    }
+
+    fn get_in(&self, locale: Locale) -> &str {
+        match self {
+            Localized::Text(text) => text,
+            Localized::PerLocale(texts) => locale
+                .keys()
+                .iter()
+                .chain(Locale::En.keys())
+                .filter_map(|key| texts.get(*key))
+                .chain(texts.values())
+                .find(|text| !text.trim().is_empty())
+                .map_or("", String::as_str),
+        }
+    }
+
+    // The locales the text is given in, or None if it's the same in all
+    pub fn locales(&self) -> Option<Vec<&str>> {
+        match self {
+            Localized::Text(_) => None,
+            Localized::PerLocale(texts) => Some(texts.keys().map(String::as_str).collect()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use regex::Regex;
+    use std::collections::BTreeSet;
+    use std::fs;
+
+    fn placeholders(message: &str) -> BTreeSet<&str> {
+        Regex::new(r"\{[a-z_]+\}")
+            .unwrap()
+            .find_iter(message)
+            .map(|m| m.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_catalogues_match() {
+        let en = Locale::En.catalogue();
+        let zh_cn = Locale::ZhCn.catalogue();
+        let en_keys: BTreeSet<_> = en.keys().collect();
+        let zh_cn_keys: BTreeSet<_> = zh_cn.keys().collect();
+        assert_eq!(en_keys, zh_cn_keys);
+        for (key, message) in en {
+            assert_eq!(placeholders(message), placeholders(&zh_cn[key]), "{key}");
+        }
+    }
+
+    #[test]
+    fn test_messages_exist() {
+        let key_re = Regex::new(r#"\bt!\(\s*"([a-z_.]+)""#).unwrap();
+        for file in fs::read_dir("src").unwrap() {
+            let source = fs::read_to_string(file.unwrap().path()).unwrap();
+            for caps in key_re.captures_iter(&source) {
+                assert!(
+                    Locale::En.catalogue().contains_key(&caps[1]),
+                    "{}",
+                    &caps[1]
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_locale() {
+        assert_eq!(Locale::from_env("zh_CN.UTF-8"), Some(Locale::ZhCn));
+        assert_eq!(Locale::from_env("en_US"), Some(Locale::En));
+        assert_eq!(Locale::from_env("C.UTF-8"), None);
+        assert_eq!("zh-CN".parse(), Ok(Locale::ZhCn));
+        assert!("fr".parse::<Locale>().is_err());
+    }
+
+    #[test]
+    fn test_fill() {
+        assert_eq!(
+            fill(
+                "{passed} of {counted} tests passed:",
+                &[("passed", 2.to_string()), ("counted", 3.to_string())]
+            ),
+            "2 of 3 tests passed:"
+        );
+    }
+
+    #[test]
+    fn test_localized() {
+        let hint: Localized = toml::from_str::<BTreeMap<String, Localized>>(
+            "[hint]\nen = \"Read the book\"\nzh = \"读一读这本书\"",
+        )
+        .unwrap()
+        .remove("hint")
+        .unwrap();
+        assert_eq!(hint.get_in(Locale::En), "Read the book");
+        assert_eq!(hint.get_in(Locale::ZhCn), "读一读这本书");
+        assert_eq!(hint.locales(), Some(vec!["en", "zh"]));
+
+        let hint =
+            Localized::PerLocale(BTreeMap::from([(String::from("zh"), String::from("提示"))]));
+        assert_eq!(hint.get_in(Locale::En), "提示");
+        let hint = Localized::PerLocale(BTreeMap::from([
+            (String::from("en"), String::new()),
+            (String::from("zh-CN"), String::from("提示")),
+        ]));
+        assert_eq!(hint.get_in(Locale::En), "提示");
+        assert_eq!(Localized::PerLocale(BTreeMap::new()).get_in(Locale::En), "");
+        let hint = Localized::Text(String::from("hint"));
+        assert_eq!(hint.get_in(Locale::ZhCn), "hint");
+        assert_eq!(hint.locales(), None);
+    }
+}
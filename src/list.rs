@@ -0,0 +1,186 @@
+use crate::exercise::{Exercise, Mode};
+use crate::graph::locked_by;
+use crate::progress::Progress;
+use crate::ListArgs;
+use serde::Serialize;
+use std::io::{self, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+// How `rustlings list` prints the exercises
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Format {
+    Table,
+    Json,
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "table" => Ok(Format::Table),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(format!("unknown format `{s}`, expected table, json or csv")),
+        }
+    }
+}
+
+// An exercise as listed in the JSON and CSV formats
+#[derive(Serialize)]
+struct Entry<'a> {
+    name: &'a str,
+    path: &'a Path,
+    mode: Mode,
+    category: String,
+    done: bool,
+    // The prerequisites that aren't done yet, if it isn't done itself
+    locked_by: Vec<&'a str>,
+    // Whether the exercise passed the last time it was verified, if it was
+    last_result: Option<&'static str>,
+    // When it was last verified, in seconds since the Unix epoch
+    last_verified_at: Option<u64>,
+}
+
+pub fn list(exercises: &[Exercise], progress: &Progress, args: &ListArgs) {
+    let filters = args.filter.clone().unwrap_or_default().to_lowercase();
+    let listed: Vec<(&Exercise, bool)> = exercises
+        .iter()
+        .map(|e| (e, progress.is_done(e)))
+        .filter(|&(e, done)| {
+            let fname = format!("{}", e.path.display());
+            let filter_cond = filters
+                .split(',')
+                .filter(|f| !f.trim().is_empty())
+                .any(|f| e.name.contains(f) || fname.contains(f));
+            let solve_cond = (done && args.solved)
+                || (!done && args.unsolved)
+                || (!args.solved && !args.unsolved);
+            solve_cond && (filter_cond || args.filter.is_none())
+        })
+        .collect();
+
+    let locked = |e, done| {
+        if done {
+            Vec::new()
+        } else {
+            locked_by(e, exercises, progress)
+        }
+    };
+    let entries = || {
+        listed.iter().map(|&(e, done)| {
+            let last = progress.get(e);
+            Entry {
+                name: &e.name,
+                path: &e.path,
+                mode: e.mode,
+                category: e.category(),
+                done,
+                locked_by: locked(e, done),
+                last_result: last.map(|p| if p.passed { "passed" } else { "failed" }),
+                last_verified_at: last.and_then(|p| p.last_attempt),
+            }
+        })
+    };
+
+    let mut out = String::new();
+    match args.format.unwrap_or(Format::Table) {
+        Format::Table => {
+            if !args.paths && !args.names {
+                out.push_str(&format!(
+                    "{:<17}\t{:<46}\t{:<7}\n",
+                    t!("list.header.name"),
+                    t!("list.header.path"),
+                    t!("list.header.status")
+                ));
+            }
+            for &(e, done) in &listed {
+                let fname = e.path.display();
+                let locked_by = locked(e, done);
+                let status = if done {
+                    t!("list.done")
+                } else if locked_by.is_empty() {
+                    t!("list.pending")
+                } else {
+                    t!("list.locked", exercises = locked_by.join(", "))
+                };
+                if args.paths {
+                    out.push_str(&format!("{fname}\n"));
+                } else if args.names {
+                    out.push_str(&format!("{}\n", e.name));
+                } else {
+                    out.push_str(&format!("{:<17}\t{fname:<46}\t{status:<7}\n", e.name));
+                }
+            }
+            let exercises_done = exercises.iter().filter(|e| progress.is_done(e)).count();
+            let percentage_progress = exercises_done as f32 / exercises.len() as f32 * 100.0;
+            out.push_str(&t!(
+                "list.progress",
+                done = exercises_done,
+                total = exercises.len(),
+                percentage = format!("{percentage_progress:.1}")
+            ));
+            out.push('\n');
+        }
+        Format::Json => {
+            let entries: Vec<Entry> = entries().collect();
+            out = serde_json::to_string_pretty(&entries).unwrap();
+            out.push('\n');
+        }
+        Format::Csv => {
+            out.push_str("name,path,mode,category,done,locked_by,last_result,last_verified_at\n");
+            for entry in entries() {
+                let fields = [
+                    entry.name.to_string(),
+                    entry.path.display().to_string(),
+                    format!("{:?}", entry.mode).to_lowercase(),
+                    entry.category,
+                    entry.done.to_string(),
+                    entry.locked_by.join(" "),
+                    entry.last_result.unwrap_or_default().to_string(),
+                    entry
+                        .last_verified_at
+                        .map(|t| t.to_string())
+                        .unwrap_or_default(),
+                ];
+                let fields: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+                out.push_str(&fields.join(","));
+                out.push('\n');
+            }
+        }
+    }
+
+    // Somehow using println! leads to the binary panicking
+    // when its output is piped.
+    // So, we're handling a Broken Pipe error and exiting with 0 anyway
+    io::stdout()
+        .lock()
+        .write_all(out.as_bytes())
+        .unwrap_or_else(|e| match e.kind() {
+            io::ErrorKind::BrokenPipe => std::process::exit(0),
+            _ => std::process::exit(1),
+        });
+}
+
+// Quote a CSV field if it needs to be
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_csv_field() {
+        assert_eq!(csv_field("intro1"), "intro1");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+}
@@ -1,30 +1,54 @@
+use crate::cache::Cache;
 use crate::exercise::{Exercise, ExerciseList};
+use crate::grade::{default_jobs, grade, ExerciseCheckList};
+use crate::i18n::Locale;
+use crate::list::{list, Format};
+use crate::progress::Progress;
 use crate::project::RustAnalyzerProject;
+use crate::report::ReportFormat;
 use crate::run::{reset, run};
-use crate::verify::verify;
+use crate::solutions::check_solutions;
+use crate::validate::validate;
+use crate::verify::{last_error_code, verify};
 use argh::FromArgs;
 use console::Emoji;
 use notify::DebouncedEvent;
 use notify::{RecommendedWatcher, RecursiveMode, Watcher};
-use serde::{Deserialize, Serialize};
 use std::ffi::OsStr;
 use std::fs;
-use std::io::{self, prelude::*};
-use std::path::Path;
+use std::io;
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::mpsc::{channel, RecvTimeoutError};
-use std::sync::{Arc, Mutex};
+use std::sync::mpsc::{channel, RecvTimeoutError, Sender};
+use std::sync::Arc;
 use std::thread;
-use std::time::Duration;
-use std::time::{UNIX_EPOCH, SystemTime};
+use std::time::{Duration, Instant};
 
 #[macro_use]
 mod ui;
+#[macro_use]
+mod i18n;
 
+mod cache;
+mod diagnostics;
+mod diff;
 mod exercise;
+mod grade;
+mod graph;
+mod harness;
+mod list;
+mod originals;
+mod process;
+mod progress;
 mod project;
+mod report;
 mod run;
+mod sandbox;
+mod solutions;
+mod tamper;
+mod tui;
+mod validate;
 mod verify;
 
 // In sync with crate version
@@ -36,9 +60,21 @@ struct Args {
     /// show outputs from the test exercises
     #[argh(switch)]
     nocapture: bool,
+    /// show the compiler's output as is, instead of only the first error
+    #[argh(switch)]
+    raw_diagnostics: bool,
+    /// verify every exercise again, even those that passed and haven't changed since
+    #[argh(switch)]
+    no_cache: bool,
+    /// decide which exercises are done by the `I AM NOT DONE` marker alone, instead of by the saved progress
+    #[argh(switch)]
+    legacy_marker: bool,
     /// show the executable version
     #[argh(switch, short = 'v')]
     version: bool,
+    /// the language of the messages: en or zh-CN (defaults to $RUSTLINGS_LANG, then $LANG)
+    #[argh(option)]
+    lang: Option<Locale>,
     #[argh(subcommand)]
     nested: Option<Subcommands>,
 }
@@ -53,17 +89,53 @@ enum Subcommands {
     Hint(HintArgs),
     List(ListArgs),
     Lsp(LspArgs),
-    CicvVerify(CicvVerifyArgs)
+    CicvVerify(CicvVerifyArgs),
+    Validate(ValidateArgs),
+    CheckSolutions(CheckSolutionsArgs),
 }
 
 #[derive(FromArgs, PartialEq, Debug)]
-#[argh(subcommand, name = "cicvverify", description = "cicvverify")]
-struct CicvVerifyArgs {}
+#[argh(subcommand, name = "cicvverify")]
+/// Grades all exercises in parallel and writes the results for the classroom
+struct CicvVerifyArgs {
+    #[argh(option, short = 'j')]
+    /// the number of exercises graded at once (defaults to the number of CPUs)
+    jobs: Option<usize>,
+    #[argh(option, default = "100.0")]
+    /// fail unless at least this percentage of the points is earned (defaults to 100)
+    fail_under: f64,
+    #[argh(switch, short = 'q')]
+    /// don't print a line for every graded exercise and the summary
+    quiet: bool,
+    #[argh(option)]
+    /// the format of the report: json (the default), junit or tap
+    report: Option<ReportFormat>,
+    #[argh(option)]
+    /// where to write the report (defaults to .github/result/check_result.json, .xml or .tap)
+    output: Option<PathBuf>,
+}
 
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "verify")]
 /// Verifies all exercises according to the recommended order
-struct VerifyArgs {}
+struct VerifyArgs {
+    #[argh(option)]
+    /// also write a report of the verified exercises: json, junit or tap
+    report: Option<ReportFormat>,
+    #[argh(option)]
+    /// where to write the report (defaults to .github/result/check_result.json, .xml or .tap)
+    output: Option<PathBuf>,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "check-solutions")]
+/// Checks that every exercise fails as shipped and passes with its solution
+struct CheckSolutionsArgs {}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "validate")]
+/// Checks info.toml and the exercises for mistakes
+struct ValidateArgs {}
 
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "watch")]
@@ -72,6 +144,9 @@ struct WatchArgs {
     /// show hints on success
     #[argh(switch)]
     success_hints: bool,
+    /// use the line based shell instead of the full-screen interface
+    #[argh(switch)]
+    line_mode: bool,
 }
 
 #[derive(FromArgs, PartialEq, Debug)]
@@ -85,16 +160,25 @@ struct RunArgs {
 
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "reset")]
-/// Resets a single exercise using "git stash -- <filename>"
+/// Resets exercises to their original versions, backing up your changes
 struct ResetArgs {
     #[argh(positional)]
     /// the name of the exercise
-    name: String,
+    name: Option<String>,
+    /// reset every exercise
+    #[argh(switch)]
+    all: bool,
+    /// reset every exercise in the given category, like `variables`
+    #[argh(option)]
+    category: Option<String>,
+    /// don't ask for confirmation
+    #[argh(switch, short = 'y')]
+    yes: bool,
 }
 
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "hint")]
-/// Returns a hint for the given exercise
+/// Reveals the next hint for the given exercise
 struct HintArgs {
     #[argh(positional)]
     /// the name of the exercise
@@ -126,32 +210,15 @@ struct ListArgs {
     #[argh(switch, short = 's')]
     /// display only exercises that have been solved
     solved: bool,
-}
-
-#[derive(Deserialize, Serialize)]
-pub struct ExerciseCheckList {
-    pub exercises: Vec<ExerciseResult>,
-    pub user_name: Option<String>,
-    pub statistics: ExerciseStatistics
-}
-
-#[derive(Deserialize, Serialize)]
-pub struct ExerciseResult {
-    pub name: String,
-    pub result: bool
-}
-
-#[derive(Deserialize, Serialize)]
-pub struct  ExerciseStatistics {
-    pub total_exercations: usize,
-    pub total_succeeds: usize,
-    pub total_failures: usize,
-    pub total_time: u32,
+    #[argh(option)]
+    /// the output format: table (the default), json or csv
+    format: Option<Format>,
 }
 
 #[tokio::main]
 async fn main() {
     let args: Args = argh::from_env();
+    i18n::init(args.lang);
 
     if args.version {
         println!("v{VERSION}");
@@ -164,173 +231,139 @@ async fn main() {
 
     if !Path::new("info.toml").exists() {
         println!(
-            "{} must be run from the rustlings directory",
-            std::env::current_exe().unwrap().to_str().unwrap()
+            "{}",
+            t!(
+                "main.not_in_rustlings_dir",
+                exe = std::env::current_exe().unwrap().display()
+            )
         );
-        println!("Try `cd rustlings/`!");
+        println!("{}", t!("main.try_cd"));
         std::process::exit(1);
     }
 
     if !rustc_exists() {
-        println!("We cannot find `rustc`.");
-        println!("Try running `rustc --version` to diagnose your problem.");
-        println!("For instructions on how to install Rust, check the README.");
+        println!("{}", t!("main.no_rustc"));
         std::process::exit(1);
     }
 
     let toml_str = &fs::read_to_string("info.toml").unwrap();
-    let exercises = toml::from_str::<ExerciseList>(toml_str).unwrap().exercises;
+    if let Some(Subcommands::Validate(_)) = args.nested {
+        validate(toml_str).unwrap_or_else(|_| std::process::exit(1));
+        std::process::exit(0);
+    }
+    let exercises = toml::from_str::<ExerciseList>(toml_str)
+        .map_err(|e| e.to_string())
+        .map(ExerciseList::into_exercises)
+        .and_then(|exercises| graph::validate(&exercises).map(|_| exercises))
+        .unwrap_or_else(|e| {
+            println!("{}", t!("main.invalid_info", error = e));
+            println!("{}", t!("main.run_validate"));
+            std::process::exit(1)
+        });
     let verbose = args.nocapture;
+    let raw_diagnostics = args.raw_diagnostics;
+    let cache = if args.no_cache {
+        Cache::disabled()
+    } else {
+        Cache::open()
+    };
+    let mut progress = Progress::load(args.legacy_marker);
 
     let command = args.nested.unwrap_or_else(|| {
-        println!("{DEFAULT_OUT}\n");
+        println!("{}\n", t!("main.default_out"));
         std::process::exit(0);
     });
     match command {
         Subcommands::List(subargs) => {
-            if !subargs.paths && !subargs.names {
-                println!("{:<17}\t{:<46}\t{:<7}", "Name", "Path", "Status");
-            }
-            let mut exercises_done: u16 = 0;
-            let filters = subargs.filter.clone().unwrap_or_default().to_lowercase();
-            exercises.iter().for_each(|e| {
-                let fname = format!("{}", e.path.display());
-                let filter_cond = filters
-                    .split(',')
-                    .filter(|f| !f.trim().is_empty())
-                    .any(|f| e.name.contains(&f) || fname.contains(&f));
-                let status = if e.looks_done() {
-                    exercises_done += 1;
-                    "Done"
-                } else {
-                    "Pending"
-                };
-                let solve_cond = {
-                    (e.looks_done() && subargs.solved)
-                        || (!e.looks_done() && subargs.unsolved)
-                        || (!subargs.solved && !subargs.unsolved)
-                };
-                if solve_cond && (filter_cond || subargs.filter.is_none()) {
-                    let line = if subargs.paths {
-                        format!("{fname}\n")
-                    } else if subargs.names {
-                        format!("{}\n", e.name)
-                    } else {
-                        format!("{:<17}\t{fname:<46}\t{status:<7}\n", e.name)
-                    };
-                    // Somehow using println! leads to the binary panicking
-                    // when its output is piped.
-                    // So, we're handling a Broken Pipe error and exiting with 0 anyway
-                    let stdout = std::io::stdout();
-                    {
-                        let mut handle = stdout.lock();
-                        handle.write_all(line.as_bytes()).unwrap_or_else(|e| {
-                            match e.kind() {
-                                std::io::ErrorKind::BrokenPipe => std::process::exit(0),
-                                _ => std::process::exit(1),
-                            };
-                        });
-                    }
-                }
-            });
-            let percentage_progress = exercises_done as f32 / exercises.len() as f32 * 100.0;
-            println!(
-                "Progress: You completed {} / {} exercises ({:.1} %).",
-                exercises_done,
-                exercises.len(),
-                percentage_progress
-            );
+            list(&exercises, &progress, &subargs);
             std::process::exit(0);
         }
 
         Subcommands::Run(subargs) => {
-            let exercise = find_exercise(&subargs.name, &exercises);
-            run(exercise, verbose).unwrap_or_else(|_| std::process::exit(1));
+            let exercise = find_exercise(&subargs.name, &exercises, &progress);
+            let locked_by = graph::locked_by(exercise, &exercises, &progress);
+            if !locked_by.is_empty() {
+                warn!("{}", t!("main.locked", exercise = exercise.name));
+                println!("{}", t!("main.locked_by", exercises = locked_by.join(", ")));
+            }
+            let result = run(exercise, verbose, raw_diagnostics);
+            progress.record(exercise, result.is_ok());
+            result.unwrap_or_else(|_| std::process::exit(1));
         }
 
         Subcommands::Reset(subargs) => {
-            let exercise = find_exercise(&subargs.name, &exercises);
+            let selected: Vec<&Exercise> = match (&subargs.name, subargs.all, &subargs.category) {
+                (Some(name), false, None) => vec![find_exercise(name, &exercises, &progress)],
+                (None, true, None) => exercises.iter().collect(),
+                (None, false, Some(category)) => {
+                    let selected: Vec<&Exercise> = exercises
+                        .iter()
+                        .filter(|e| &e.category() == category)
+                        .collect();
+                    if selected.is_empty() {
+                        println!("{}", t!("main.no_category", category = category));
+                        std::process::exit(1);
+                    }
+                    selected
+                }
+                _ => {
+                    eprintln!("{}", t!("main.reset_what"));
+                    std::process::exit(1);
+                }
+            };
 
-            reset(exercise).unwrap_or_else(|_| std::process::exit(1));
+            reset(&selected, subargs.yes).unwrap_or_else(|_| std::process::exit(1));
         }
 
         Subcommands::Hint(subargs) => {
-            let exercise = find_exercise(&subargs.name, &exercises);
+            let exercise = find_exercise(&subargs.name, &exercises, &progress);
 
-            println!("{}", exercise.hint);
+            reveal_hint(
+                exercise,
+                &mut progress,
+                &t!("hint.again", exercise = exercise.name),
+            );
         }
 
-        Subcommands::Verify(_subargs) => {
-            verify(&exercises, (0, exercises.len()), verbose, false)
-                .unwrap_or_else(|_| std::process::exit(1));
+        Subcommands::Verify(subargs) => {
+            sandbox::warn_if_unconfined();
+            let start = Instant::now();
+            let verified = verify(
+                &exercises,
+                (0, exercises.len()),
+                verbose,
+                false,
+                raw_diagnostics,
+                &cache,
+                &mut progress,
+            );
+            if subargs.report.is_some() || subargs.output.is_some() {
+                let check_list = grade::check_list(verified.results, start.elapsed());
+                write_report(subargs.report, subargs.output, &check_list);
+            }
+            if verified.stopped_at.is_some() {
+                std::process::exit(1);
+            }
         }
 
-        Subcommands::CicvVerify(_subargs) => {
-            // let toml_str = &fs::read_to_string("info.toml").unwrap();
-            // exercises = toml::from_str::<ExerciseList>(toml_str).unwrap().exercises;
-            let now_start = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-            let rights = Arc::new(Mutex::new(0));
-            let alls = exercises.len();
-
-            let exercise_check_list =  Arc::new(Mutex::new(
-                ExerciseCheckList {
-                    exercises: vec![], 
-                    user_name:  None, 
-                    statistics: ExerciseStatistics { 
-                        total_exercations: alls, 
-                        total_succeeds: 0, 
-                        total_failures: 0, 
-                        total_time: 0, 
-                    }
-                }
-            ));
-
-            let mut tasks = vec![];
-            for exercise in exercises {
-                let now_start = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-                let inner_exercise = exercise;
-                let c_mutex = Arc::clone(&rights);
-                let exercise_check_list_ref = Arc::clone(&exercise_check_list);
-                let _verbose = verbose.clone();
-                let t = tokio::task::spawn( async move {
-                    match run(&inner_exercise, true) {
-                    // match verify(vec![&inner_exercise], (0, 1), true, true) {
-                        Ok(_) => {
-                            *c_mutex.lock().unwrap() += 1;
-                            println!("{}执行成功", inner_exercise.name);
-                            println!("总的题目数: {}", alls);
-                            println!("当前做正确的题目数: {}", *c_mutex.lock().unwrap());
-                            let now_end = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-                            println!("当前修改试卷耗时: {} s", now_end - now_start);
-                            exercise_check_list_ref.lock().unwrap().exercises.push(ExerciseResult{ 
-                                name: inner_exercise.name, result: true,
-                            });
-                            exercise_check_list_ref.lock().unwrap().statistics.total_succeeds += 1;
-                        },
-                        Err(_) => {
-                            println!("{}执行失败", inner_exercise.name);
-                            println!("总的题目数: {}", alls);
-                            println!("当前做正确的题目数: {}", *c_mutex.lock().unwrap());
-                            let now_end = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-                            println!("当前修改试卷耗时: {} s", now_end - now_start);
-                            exercise_check_list_ref.lock().unwrap().exercises.push(ExerciseResult{ 
-                                name: inner_exercise.name, result: false,
-                            });
-                            exercise_check_list_ref.lock().unwrap().statistics.total_failures += 1;
-                        }
-                    }
-                });
-                tasks.push(t);
+        Subcommands::CicvVerify(subargs) => {
+            sandbox::warn_if_unconfined();
+            let jobs = subargs.jobs.unwrap_or_else(default_jobs);
+            let exercise_check_list = grade(exercises, jobs, subargs.quiet, &progress).await;
+            write_report(subargs.report, subargs.output, &exercise_check_list);
+            let percentage = exercise_check_list.statistics.percentage();
+            if percentage < subargs.fail_under {
+                println!(
+                    "{}",
+                    t!(
+                        "main.fail_under",
+                        percentage = format!("{percentage:.1}"),
+                        required = subargs.fail_under
+                    )
+                );
+                std::process::exit(1);
             }
-            for task in tasks { task.await.unwrap(); }
-            let now_end = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-            let total_time = now_end - now_start;
-            println!("===============================试卷批改完成,总耗时: {} s; ==================================", total_time);
-            let exercise_check_list_ref = Arc::clone(&exercise_check_list);
-            exercise_check_list_ref.lock().unwrap().statistics.total_time = total_time as u32;
-            let serialized = serde_json::to_string_pretty(&*exercise_check_list.lock().unwrap()).unwrap();
-            fs::write(".github/result/check_result.json", serialized).unwrap();
-        },
+        }
 
         Subcommands::Lsp(_subargs) => {
             let mut project = RustAnalyzerProject::new();
@@ -338,110 +371,175 @@ async fn main() {
                 .get_sysroot_src()
                 .expect("Couldn't find toolchain path, do you have `rustc` installed?");
             project
-                .exercises_to_json()
+                .exercises_to_json(&exercises)
                 .expect("Couldn't parse rustlings exercises files");
 
             if project.crates.is_empty() {
-                println!("Failed find any exercises, make sure you're in the `rustlings` folder");
+                println!("{}", t!("lsp.no_exercises"));
             } else if project.write_to_disk().is_err() {
-                println!("Failed to write rust-project.json to disk for rust-analyzer");
+                println!("{}", t!("lsp.write_failed"));
             } else {
-                println!("Successfully generated rust-project.json");
-                println!("rust-analyzer will now parse exercises, restart your language server or editor")
+                println!("{}", t!("lsp.written"));
             }
         }
 
-        Subcommands::Watch(_subargs) => match watch(&exercises, verbose, _subargs.success_hints) {
-            Err(e) => {
-                println!(
-                    "Error: Could not watch your progress. Error message was {:?}.",
-                    e
-                );
-                println!("Most likely you've run out of disk space or your 'inotify limit' has been reached.");
-                std::process::exit(1);
-            }
-            Ok(WatchStatus::Finished) => {
-                println!(
-                    "{emoji} All exercises completed! {emoji}",
-                    emoji = Emoji("🎉", "★")
-                );
-                println!("\n{FENISH_LINE}\n");
-            }
-            Ok(WatchStatus::Unfinished) => {
-                println!("We hope you're enjoying learning about Rust!");
-                println!("If you want to continue working on the exercises at a later point, you can simply run `rustlings watch` again");
+        Subcommands::CheckSolutions(_) => {
+            check_solutions(&exercises).unwrap_or_else(|_| std::process::exit(1));
+        }
+
+        Subcommands::Validate(_) => unreachable!("validated before loading the exercises"),
+
+        Subcommands::Watch(subargs) => {
+            let status = if subargs.line_mode || !supports_tui() {
+                watch(
+                    &exercises,
+                    verbose,
+                    subargs.success_hints,
+                    raw_diagnostics,
+                    &cache,
+                    &mut progress,
+                )
+            } else {
+                tui::watch(
+                    &exercises,
+                    verbose,
+                    subargs.success_hints,
+                    raw_diagnostics,
+                    &cache,
+                    &mut progress,
+                )
+            };
+            match status {
+                Err(e) => {
+                    println!("{}", t!("watch.failed", error = format!("{e:?}")));
+                    std::process::exit(1);
+                }
+                Ok(WatchStatus::Finished) => {
+                    println!("{}", t!("watch.completed", emoji = Emoji("🎉", "★")));
+                    println!("\n{FENISH_LINE}\n\n{}\n", t!("main.finished"));
+                }
+                Ok(WatchStatus::Unfinished) => {
+                    println!("{}", t!("watch.unfinished"));
+                }
             }
-        },
+        }
     }
 }
 
-fn spawn_watch_shell(
-    failed_exercise_hint: &Arc<Mutex<Option<String>>>,
-    should_quit: Arc<AtomicBool>,
-) {
-    let failed_exercise_hint = Arc::clone(failed_exercise_hint);
-    println!("Welcome to watch mode! You can type 'help' to get an overview of the commands you can use here.");
+// Reveal the next hint of an exercise and print all hints revealed so far.
+// `again` tells how to ask for the next hint.
+fn reveal_hint(exercise: &Exercise, progress: &mut Progress, again: &str) {
+    let hints = exercise.hints();
+    if hints.is_empty() {
+        println!("{}", t!("hint.none", exercise = exercise.name));
+        return;
+    }
+    let unlocked = progress.reveal_hint(exercise);
+    let revealed = progress.hints_revealed(exercise).min(hints.len());
+    for (i, hint) in hints.iter().take(revealed).enumerate() {
+        if hints.len() > 1 {
+            println!(
+                "{}",
+                t!("hint.numbered", number = i + 1, total = hints.len())
+            );
+        }
+        println!("{hint}");
+    }
+    if revealed < hints.len() {
+        if unlocked {
+            println!("{}", t!("hint.next", again = again));
+        } else {
+            println!("{}", t!("hint.locked"));
+        }
+    }
+}
+
+// The hint command of the watch shell asks the watch loop for the next
+// hint, since that's where the progress is kept
+fn spawn_watch_shell(hint_requests: Sender<()>, should_quit: Arc<AtomicBool>) {
+    println!("{}", t!("watch.welcome"));
     thread::spawn(move || loop {
         let mut input = String::new();
         match io::stdin().read_line(&mut input) {
             Ok(_) => {
                 let input = input.trim();
                 if input == "hint" {
-                    if let Some(hint) = &*failed_exercise_hint.lock().unwrap() {
-                        println!("{hint}");
+                    let _ = hint_requests.send(());
+                } else if input == "e" || input == "explain" {
+                    match last_error_code() {
+                        Some(code) => {
+                            if let Err(e) =
+                                Command::new("rustc").args(["--explain", &code]).status()
+                            {
+                                println!("{}", t!("watch.explain_failed", code = code, error = e));
+                            }
+                        }
+                        None => println!("{}", t!("watch.nothing_to_explain")),
                     }
                 } else if input == "clear" {
                     println!("\x1B[2J\x1B[1;1H");
                 } else if input.eq("quit") {
                     should_quit.store(true, Ordering::SeqCst);
-                    println!("Bye!");
+                    println!("{}", t!("watch.bye"));
                 } else if input.eq("help") {
-                    println!("Commands available to you in watch mode:");
-                    println!("  hint   - prints the current exercise's hint");
-                    println!("  clear  - clears the screen");
-                    println!("  quit   - quits watch mode");
-                    println!("  !<cmd> - executes a command, like `!rustc --explain E0381`");
-                    println!("  help   - displays this help message");
-                    println!();
-                    println!("Watch mode automatically re-evaluates the current exercise");
-                    println!("when you edit a file's contents.")
+                    println!("{}", t!("watch.help"));
                 } else if let Some(cmd) = input.strip_prefix('!') {
                     let parts: Vec<&str> = cmd.split_whitespace().collect();
                     if parts.is_empty() {
-                        println!("no command provided");
+                        println!("{}", t!("watch.no_command"));
                     } else if let Err(e) = Command::new(parts[0]).args(&parts[1..]).status() {
-                        println!("failed to execute command `{}`: {}", cmd, e);
+                        println!("{}", t!("watch.command_failed", command = cmd, error = e));
                     }
                 } else {
-                    println!("unknown command: {input}");
+                    println!("{}", t!("watch.unknown_command", input = input));
                 }
             }
-            Err(error) => println!("error reading command: {error}"),
+            Err(error) => println!("{}", t!("watch.read_failed", error = error)),
         }
     });
 }
 
-fn find_exercise<'a>(name: &str, exercises: &'a [Exercise]) -> &'a Exercise {
+// Write the report `--report` and `--output` ask for, in JSON by default
+fn write_report(
+    format: Option<ReportFormat>,
+    output: Option<PathBuf>,
+    results: &ExerciseCheckList,
+) {
+    let format = format.unwrap_or(ReportFormat::Json);
+    let output = output.unwrap_or_else(|| format.default_output());
+    if let Err(e) = report::write(format, &output, results) {
+        println!(
+            "{}",
+            t!("main.report_failed", path = output.display(), error = e)
+        );
+        std::process::exit(1);
+    }
+}
+
+fn find_exercise<'a>(name: &str, exercises: &'a [Exercise], progress: &Progress) -> &'a Exercise {
     if name.eq("next") {
-        exercises
-            .iter()
-            .find(|e| !e.looks_done())
-            .unwrap_or_else(|| {
-                println!("🎉 Congratulations! You have done all the exercises!");
-                println!("🔚 There are no more exercises to do next!");
-                std::process::exit(1)
-            })
+        graph::next(exercises, progress).unwrap_or_else(|| {
+            println!("{}", t!("main.all_done"));
+            std::process::exit(1)
+        })
     } else {
         exercises
             .iter()
             .find(|e| e.name == name)
             .unwrap_or_else(|| {
-                println!("No exercise found for '{name}'!");
+                println!("{}", t!("main.no_exercise", name = name));
                 std::process::exit(1)
             })
     }
 }
 
+// Whether the full-screen watch mode can work in this terminal
+fn supports_tui() -> bool {
+    console::Term::stdout().is_term()
+        && console::Term::stderr().is_term()
+        && std::env::var("TERM").map_or(true, |term| term != "dumb")
+}
+
 enum WatchStatus {
     Finished,
     Unfinished,
@@ -451,6 +549,9 @@ fn watch(
     exercises: &[Exercise],
     verbose: bool,
     success_hints: bool,
+    raw_diagnostics: bool,
+    cache: &Cache,
+    progress: &mut Progress,
 ) -> notify::Result<WatchStatus> {
     /* Clears the terminal with an ANSI escape code.
     Works in UNIX and newer Windows terminals. */
@@ -466,45 +567,61 @@ fn watch(
 
     clear_screen();
 
-    let to_owned_hint = |t: &Exercise| t.hint.to_owned();
-    let failed_exercise_hint = match verify(
-        exercises.iter(),
+    // Prerequisites are verified before the exercises that require them
+    let ordered = graph::order(exercises);
+    let mut failed_exercise = match verify(
+        ordered.iter().copied(),
         (0, exercises.len()),
         verbose,
         success_hints,
-    ) {
-        Ok(_) => return Ok(WatchStatus::Finished),
-        Err(exercise) => Arc::new(Mutex::new(Some(to_owned_hint(exercise)))),
+        raw_diagnostics,
+        cache,
+        progress,
+    )
+    .stopped_at
+    {
+        None => return Ok(WatchStatus::Finished),
+        Some(exercise) => exercise,
     };
-    spawn_watch_shell(&failed_exercise_hint, Arc::clone(&should_quit));
+    print_explain_shortcut();
+    let (hint_tx, hint_rx) = channel();
+    spawn_watch_shell(hint_tx, Arc::clone(&should_quit));
     loop {
-        match rx.recv_timeout(Duration::from_secs(1)) {
+        match rx.recv_timeout(Duration::from_millis(200)) {
             Ok(event) => match event {
-                DebouncedEvent::Create(b) | DebouncedEvent::Chmod(b) | DebouncedEvent::Write(b) => {
-                    if b.extension() == Some(OsStr::new("rs")) && b.exists() {
-                        let filepath = b.as_path().canonicalize().unwrap();
-                        let pending_exercises = exercises
-                            .iter()
-                            .find(|e| filepath.ends_with(&e.path))
-                            .into_iter()
-                            .chain(
-                                exercises
-                                    .iter()
-                                    .filter(|e| !e.looks_done() && !filepath.ends_with(&e.path)),
-                            );
-                        let num_done = exercises.iter().filter(|e| e.looks_done()).count();
-                        clear_screen();
-                        match verify(
-                            pending_exercises,
-                            (num_done, exercises.len()),
-                            verbose,
-                            success_hints,
-                        ) {
-                            Ok(_) => return Ok(WatchStatus::Finished),
-                            Err(exercise) => {
-                                let mut failed_exercise_hint = failed_exercise_hint.lock().unwrap();
-                                *failed_exercise_hint = Some(to_owned_hint(exercise));
-                            }
+                DebouncedEvent::Create(b) | DebouncedEvent::Chmod(b) | DebouncedEvent::Write(b)
+                    if b.extension() == Some(OsStr::new("rs")) && b.exists() =>
+                {
+                    let filepath = b.as_path().canonicalize().unwrap();
+                    let pending_exercises: Vec<&Exercise> = ordered
+                        .iter()
+                        .copied()
+                        .find(|e| e.owns(&filepath))
+                        .into_iter()
+                        .chain(
+                            ordered
+                                .iter()
+                                .copied()
+                                .filter(|e| !progress.is_done(e) && !e.owns(&filepath)),
+                        )
+                        .collect();
+                    let num_done = exercises.iter().filter(|e| progress.is_done(e)).count();
+                    clear_screen();
+                    match verify(
+                        pending_exercises,
+                        (num_done, exercises.len()),
+                        verbose,
+                        success_hints,
+                        raw_diagnostics,
+                        cache,
+                        progress,
+                    )
+                    .stopped_at
+                    {
+                        None => return Ok(WatchStatus::Finished),
+                        Some(exercise) => {
+                            failed_exercise = exercise;
+                            print_explain_shortcut();
                         }
                     }
                 }
@@ -513,7 +630,10 @@ fn watch(
             Err(RecvTimeoutError::Timeout) => {
                 // the timeout expired, just check the `should_quit` variable below then loop again
             }
-            Err(e) => println!("watch error: {e:?}"),
+            Err(e) => println!("{}", t!("watch.error", error = format!("{e:?}"))),
+        }
+        while hint_rx.try_recv().is_ok() {
+            reveal_hint(failed_exercise, progress, &t!("watch.hint_again"));
         }
         // Check if we need to exit
         if should_quit.load(Ordering::SeqCst) {
@@ -522,9 +642,16 @@ fn watch(
     }
 }
 
+// Point out the watch mode shortcut to `rustc --explain` for the current error
+fn print_explain_shortcut() {
+    if let Some(code) = last_error_code() {
+        println!("{}", t!("watch.explain", code = code));
+    }
+}
+
 fn rustc_exists() -> bool {
     Command::new("rustc")
-        .args(&["--version"])
+        .args(["--version"])
         .stdout(Stdio::null())
         .spawn()
         .and_then(|mut child| child.wait())
@@ -532,34 +659,6 @@ fn rustc_exists() -> bool {
         .unwrap_or(false)
 }
 
-const DEFAULT_OUT: &str = r#"Thanks for installing Rustlings!
-
-Is this your first time? Don't worry, Rustlings was made for beginners! We are
-going to teach you a lot of things about Rust, but before we can get
-started, here's a couple of notes about how Rustlings operates:
-
-1. The central concept behind Rustlings is that you solve exercises. These
-   exercises usually have some sort of syntax error in them, which will cause
-   them to fail compilation or testing. Sometimes there's a logic error instead
-   of a syntax error. No matter what error, it's your job to find it and fix it!
-   You'll know when you fixed it because then, the exercise will compile and
-   Rustlings will be able to move on to the next exercise.
-2. If you run Rustlings in watch mode (which we recommend), it'll automatically
-   start with the first exercise. Don't get confused by an error message popping
-   up as soon as you run Rustlings! This is part of the exercise that you're
-   supposed to solve, so open the exercise file in an editor and start your
-   detective work!
-3. If you're stuck on an exercise, there is a helpful hint you can view by typing
-   'hint' (in watch mode), or running `rustlings hint exercise_name`.
-4. If an exercise doesn't make sense to you, feel free to open an issue on GitHub!
-   (https://github.com/rust-lang/rustlings/issues/new). We look at every issue,
-   and sometimes, other learners do too so you can help each other out!
-5. If you want to use `rust-analyzer` with exercises, which provides features like
-   autocompletion, run the command `rustlings lsp`.
-
-Got all that? Great! To get started, run `rustlings watch` in order to get the first
-exercise. Make sure to have your editor open!"#;
-
 const FENISH_LINE: &str = r#"+----------------------------------------------------+
 |          You made it to the Fe-nish line!          |
 +--------------------------  ------------------------+
@@ -578,14 +677,7 @@ const FENISH_LINE: &str = r#"+--------------------------------------------------
          ▒▒    ▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒    ▒▒
        ▒▒    ▒▒    ▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒    ▒▒    ▒▒
        ▒▒  ▒▒    ▒▒                  ▒▒    ▒▒  ▒▒
-           ▒▒  ▒▒                      ▒▒  ▒▒
-
-We hope you enjoyed learning about the various aspects of Rust!
-If you noticed any issues, please don't hesitate to report them to our repo.
-You can also contribute your own exercises to help the greater community!
-
-Before reporting an issue or contributing, please read our guidelines:
-https://github.com/rust-lang/rustlings/blob/main/CONTRIBUTING.md"#;
+           ▒▒  ▒▒                      ▒▒  ▒▒"#;
 
 const WELCOME: &str = r#"       welcome to...
                  _   _ _
@@ -0,0 +1,115 @@
+use crate::exercise::{files_below, Exercise};
+use crate::progress::now;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// Pristine copies of the exercises, shipped with the course and mirroring
+// the layout of `exercises/`
+const ORIGINALS_DIR: &str = "originals";
+const EXERCISES_DIR: &str = "exercises";
+// The learner's versions of reset exercises
+const BACKUPS_DIR: &str = ".rustlings/backups";
+
+// What resetting a single file did
+pub enum FileReset {
+    // The file was restored, and the learner's version, if there was one,
+    // saved to the backup
+    Restored { backup: Option<PathBuf> },
+    // The file was already pristine
+    Unchanged,
+    // The file was added to an exercise that is a directory. It was removed
+    // and saved to the backup.
+    Removed { backup: PathBuf },
+}
+
+// Where the pristine copy of an exercise file is kept
+pub fn original_path(input: &Path) -> PathBuf {
+    original_below(Path::new(EXERCISES_DIR), input)
+}
+
+// Where the pristine copy of a file below an exercises directory is kept,
+// in the originals directory next to it
+pub fn original_below(exercises_dir: &Path, input: &Path) -> PathBuf {
+    let relative = input.strip_prefix(exercises_dir).unwrap_or(input);
+    let parent = exercises_dir.parent().unwrap_or(Path::new(""));
+    parent.join(ORIGINALS_DIR).join(relative)
+}
+
+// Restore the files of an exercise from their pristine copies. Every file
+// that was changed is backed up first. An exercise that is a directory is
+// restored as a whole: deleted files come back and added ones are removed.
+pub fn restore(exercise: &Exercise) -> io::Result<Vec<(PathBuf, FileReset)>> {
+    let mut restored = Vec::new();
+    let inputs = if exercise.path.is_dir() {
+        let original_dir = original_path(&exercise.path);
+        let originals: Vec<PathBuf> = files_below(&original_dir)
+            .into_iter()
+            .filter_map(|file| {
+                let relative = file.strip_prefix(&original_dir).ok()?;
+                Some(exercise.path.join(relative))
+            })
+            .collect();
+        if originals.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                t!(
+                    "reset.no_original_dir",
+                    exercise = exercise.path.display(),
+                    path = original_dir.display()
+                ),
+            ));
+        }
+        for file in exercise.files() {
+            if !originals.contains(&file) {
+                let backup = back_up(&file, &fs::read(&file)?)?;
+                fs::remove_file(&file)?;
+                restored.push((file, FileReset::Removed { backup }));
+            }
+        }
+        originals
+    } else {
+        exercise.inputs()
+    };
+
+    for input in inputs {
+        let original_path = original_path(&input);
+        let original = fs::read(&original_path).map_err(|e| {
+            io::Error::new(
+                e.kind(),
+                t!(
+                    "reset.no_original",
+                    exercise = input.display(),
+                    path = original_path.display()
+                ),
+            )
+        })?;
+        let current = fs::read(&input).ok();
+        if current.as_deref() == Some(original.as_slice()) {
+            restored.push((input, FileReset::Unchanged));
+            continue;
+        }
+        let backup = match current {
+            Some(current) => Some(back_up(&input, &current)?),
+            None => None,
+        };
+        if let Some(parent) = input.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&input, original)?;
+        restored.push((input, FileReset::Restored { backup }));
+    }
+    Ok(restored)
+}
+
+// Save the learner's version of a file to the backups
+fn back_up(input: &Path, contents: &[u8]) -> io::Result<PathBuf> {
+    let mut backup = Path::new(BACKUPS_DIR).join(input).into_os_string();
+    backup.push(format!(".{}", now()));
+    let backup = PathBuf::from(backup);
+    if let Some(parent) = backup.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&backup, contents)?;
+    Ok(backup)
+}
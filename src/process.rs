@@ -0,0 +1,126 @@
+use std::io::{self, Read, Write};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// The captured output of a child process that ran with a time limit
+pub struct Output {
+    // The exit status, or None if the process was killed for running too long
+    pub status: Option<ExitStatus>,
+    pub stdout: Vec<u8>,
+    pub stderr: Vec<u8>,
+}
+
+impl Output {
+    pub fn success(&self) -> bool {
+        self.status.is_some_and(|status| status.success())
+    }
+
+    pub fn timed_out(&self) -> bool {
+        self.status.is_none()
+    }
+}
+
+// Like `Command::output`, but kills the child and everything it spawned
+// once `timeout` has passed.
+pub fn output_with_timeout(command: &mut Command, timeout: Duration) -> io::Result<Output> {
+    output_with_input(command, None, timeout)
+}
+
+// Like `output_with_timeout`, but feeds `input` to the child's stdin
+pub fn output_with_input(
+    command: &mut Command,
+    input: Option<&str>,
+    timeout: Duration,
+) -> io::Result<Output> {
+    command
+        .stdin(if input.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    isolate(command);
+
+    let mut child = command.spawn()?;
+    // Written on its own thread, so a child that doesn't read all of its
+    // input can't block us. Dropping the pipe closes the child's stdin.
+    if let (Some(mut stdin), Some(input)) = (child.stdin.take(), input) {
+        let input = input.to_owned();
+        thread::spawn(move || {
+            let _ = stdin.write_all(input.as_bytes());
+        });
+    }
+    let stdout = read_to_end(child.stdout.take());
+    let stderr = read_to_end(child.stderr.take());
+
+    let start = Instant::now();
+    let mut poll = Duration::from_millis(1);
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break Some(status);
+        }
+        if start.elapsed() >= timeout {
+            kill(&mut child);
+            child.wait()?;
+            break None;
+        }
+        thread::sleep(poll);
+        poll = (poll * 2).min(Duration::from_millis(50));
+    };
+
+    Ok(Output {
+        status,
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+    })
+}
+
+// Drain a pipe on its own thread, so a chatty child never blocks on a full pipe
+fn read_to_end(pipe: Option<impl Read + Send + 'static>) -> thread::JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut buf = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut buf);
+        }
+        buf
+    })
+}
+
+// Put the child in its own process group so that the whole group can be
+// killed on timeout. On Linux the child is also killed when we die, so
+// interrupting rustlings doesn't leave a runaway exercise behind.
+#[cfg(unix)]
+fn isolate(command: &mut Command) {
+    use std::os::unix::process::CommandExt;
+
+    command.process_group(0);
+    #[cfg(target_os = "linux")]
+    // SAFETY: prctl is async-signal-safe and touches no memory of the parent.
+    unsafe {
+        command.pre_exec(|| {
+            if libc::prctl(libc::PR_SET_PDEATHSIG, libc::SIGKILL) == -1 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+}
+
+#[cfg(not(unix))]
+fn isolate(_command: &mut Command) {}
+
+#[cfg(unix)]
+fn kill(child: &mut Child) {
+    // The child leads its own process group, so its pid is the group id
+    // SAFETY: kill has no memory safety requirements.
+    unsafe {
+        libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL);
+    }
+}
+
+#[cfg(not(unix))]
+fn kill(child: &mut Child) {
+    let _ = child.kill();
+}
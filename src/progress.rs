@@ -0,0 +1,275 @@
+use crate::exercise::{Exercise, State};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Where the progress is saved, next to info.toml
+const STATE_FILE: &str = ".rustlings-state.json";
+
+// What is known about the attempts at one exercise
+#[derive(Deserialize, Serialize, Default, Debug)]
+pub struct ExerciseProgress {
+    // Whether the exercise passed the last time it was verified
+    pub passed: bool,
+    // How many times the exercise was verified
+    pub attempts: u32,
+    // When the exercise was first and last verified, in seconds since the Unix epoch
+    pub first_attempt: Option<u64>,
+    pub last_attempt: Option<u64>,
+    // When the exercise last passed
+    pub passed_at: Option<u64>,
+    // The hash of the exercise's source when it last passed
+    pub source_hash: Option<String>,
+    // How many of the exercise's hints were revealed
+    #[serde(default)]
+    pub hints_revealed: usize,
+    // The number of attempts when the last hint was revealed
+    #[serde(default)]
+    pub attempts_at_last_hint: u32,
+}
+
+// The progress through the exercises, saved in `.rustlings-state.json`.
+// Unlike the `I AM NOT DONE` marker, which anyone can delete, an exercise
+// only counts as done here once it has actually been verified, and only
+// for as long as its source stays the way it was when it passed.
+#[derive(Deserialize, Serialize, Default, Debug)]
+pub struct Progress {
+    #[serde(default)]
+    exercises: BTreeMap<String, ExerciseProgress>,
+    #[serde(skip)]
+    path: PathBuf,
+    // Decide completion by the marker alone, like older versions did
+    #[serde(skip)]
+    legacy_marker: bool,
+}
+
+impl Progress {
+    // Load the saved progress. A missing or unreadable state file means
+    // nothing has been verified yet.
+    pub fn load(legacy_marker: bool) -> Progress {
+        let path = PathBuf::from(STATE_FILE);
+        Progress {
+            legacy_marker,
+            ..read(&path)
+        }
+    }
+
+    // Whether the exercise is done. The marker is still honoured: an
+    // exercise that has it is never done, even if it passes.
+    pub fn is_done(&self, exercise: &Exercise) -> bool {
+        if exercise.state() != State::Done {
+            return false;
+        }
+        self.legacy_marker || self.verified(exercise)
+    }
+
+    // Whether the exercise passed and hasn't changed since
+    pub fn verified(&self, exercise: &Exercise) -> bool {
+        match self.exercises.get(&exercise.name) {
+            Some(progress) if progress.passed => {
+                progress.source_hash.is_some() && progress.source_hash == exercise.source_hash()
+            }
+            _ => false,
+        }
+    }
+
+    pub fn get(&self, exercise: &Exercise) -> Option<&ExerciseProgress> {
+        self.exercises.get(&exercise.name)
+    }
+
+    pub fn hints_revealed(&self, exercise: &Exercise) -> usize {
+        self.exercises
+            .get(&exercise.name)
+            .map_or(0, |progress| progress.hints_revealed)
+    }
+
+    // Whether the next hint of the exercise may be revealed. The first hint
+    // always may; every further hint only after the exercise was attempted
+    // again since the previous one.
+    pub fn hint_unlocked(&self, exercise: &Exercise) -> bool {
+        match self.exercises.get(&exercise.name) {
+            Some(progress) if progress.hints_revealed > 0 => {
+                progress.attempts > progress.attempts_at_last_hint
+            }
+            _ => true,
+        }
+    }
+
+    // Reveal the next hint of the exercise, if there is one and it's
+    // unlocked. Returns whether a hint was revealed.
+    pub fn reveal_hint(&mut self, exercise: &Exercise) -> bool {
+        if self.hints_revealed(exercise) >= exercise.hints().len() || !self.hint_unlocked(exercise)
+        {
+            return false;
+        }
+        let progress = self.exercises.entry(exercise.name.clone()).or_default();
+        progress.hints_revealed += 1;
+        progress.attempts_at_last_hint = progress.attempts;
+        if let Err(e) = self.save(&exercise.name) {
+            warn!("{}", t!("progress.save_failed", error = e));
+        }
+        true
+    }
+
+    // Record the outcome of verifying the exercise and save it right away,
+    // so that no progress is lost if rustlings is interrupted
+    pub fn record(&mut self, exercise: &Exercise, passed: bool) {
+        let now = now();
+        let progress = self.exercises.entry(exercise.name.clone()).or_default();
+        progress.passed = passed;
+        progress.attempts += 1;
+        progress.first_attempt.get_or_insert(now);
+        progress.last_attempt = Some(now);
+        if passed {
+            progress.passed_at = Some(now);
+            progress.source_hash = exercise.source_hash();
+        }
+        if let Err(e) = self.save(&exercise.name) {
+            warn!("{}", t!("progress.save_failed", error = e));
+        }
+    }
+
+    // Save the progress after the named exercise changed. Other rustlings
+    // processes may have saved since this one loaded the state file, so
+    // it's read again first and only this exercise is taken from memory.
+    fn save(&mut self, name: &str) -> io::Result<()> {
+        for (other, progress) in read(&self.path).exercises {
+            if other != name {
+                self.exercises.insert(other, progress);
+            }
+        }
+        let json = serde_json::to_string_pretty(self)?;
+        // Write to a temporary file of this process first, so that the state
+        // file is never left half written
+        let tmp = self
+            .path
+            .with_extension(format!("json.{}.tmp", process::id()));
+        fs::write(&tmp, json)?;
+        fs::rename(&tmp, &self.path)
+    }
+}
+
+// The progress in a state file. A missing or unreadable one means nothing
+// has been verified yet.
+fn read(path: &Path) -> Progress {
+    let progress: Progress = fs::read_to_string(path)
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default();
+    Progress {
+        path: path.to_path_buf(),
+        ..progress
+    }
+}
+
+// The current time in seconds since the Unix epoch
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::exercise::Mode;
+    use crate::i18n::Localized;
+    use std::env;
+
+    #[test]
+    fn test_progress_follows_source() {
+        let dir = env::temp_dir().join(format!("rustlings-progress-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("progress.rs");
+        fs::write(&path, "fn main() {}\n").unwrap();
+        let exercise = Exercise {
+            name: String::from("progress"),
+            path: path.clone(),
+            mode: Mode::Compile,
+            ..Default::default()
+        };
+        let mut progress = Progress {
+            path: dir.join(STATE_FILE),
+            ..Progress::default()
+        };
+
+        assert!(!progress.is_done(&exercise));
+        progress.record(&exercise, false);
+        assert!(!progress.is_done(&exercise));
+        progress.record(&exercise, true);
+        assert!(progress.is_done(&exercise));
+        assert_eq!(progress.exercises["progress"].attempts, 2);
+
+        let saved: Progress =
+            serde_json::from_str(&fs::read_to_string(dir.join(STATE_FILE)).unwrap()).unwrap();
+        assert!(saved.exercises["progress"].passed);
+
+        // Changing the source undoes the exercise, and so does the marker
+        fs::write(&path, "fn main() { }\n").unwrap();
+        assert!(!progress.is_done(&exercise));
+        progress.record(&exercise, true);
+        fs::write(&path, "// I AM NOT DONE\nfn main() { }\n").unwrap();
+        assert!(!progress.is_done(&exercise));
+
+        // Only one more hint per attempt
+        progress
+            .exercises
+            .get_mut("progress")
+            .unwrap()
+            .hints_revealed = 0;
+        let hinted = Exercise {
+            hint: Localized::Text(String::from("first")),
+            hints: vec![Localized::Text(String::from("second"))],
+            ..exercise
+        };
+        assert!(progress.reveal_hint(&hinted));
+        assert!(!progress.reveal_hint(&hinted));
+        progress.record(&hinted, false);
+        assert!(progress.reveal_hint(&hinted));
+        progress.record(&hinted, false);
+        assert!(!progress.reveal_hint(&hinted));
+        assert_eq!(progress.hints_revealed(&hinted), 2);
+        let exercise = hinted;
+
+        progress.legacy_marker = true;
+        assert!(!progress.is_done(&exercise));
+        fs::write(&path, "fn main() {}\n").unwrap();
+        assert!(progress.is_done(&exercise));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_concurrent_saves_keep_each_other() {
+        let dir = env::temp_dir().join(format!("rustlings-progress-merge-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let exercise = |name: &str| Exercise {
+            name: String::from(name),
+            path: dir.join(format!("{name}.rs")),
+            mode: Mode::Compile,
+            ..Default::default()
+        };
+        let (watching, running) = (exercise("watching"), exercise("running"));
+        fs::write(&watching.path, "fn main() {}\n").unwrap();
+        fs::write(&running.path, "fn main() {}\n").unwrap();
+
+        // Two processes that loaded the state before either saved
+        let mut watch = read(&dir.join(STATE_FILE));
+        let mut run = read(&dir.join(STATE_FILE));
+        watch.record(&watching, true);
+        run.record(&running, true);
+        watch.record(&watching, false);
+
+        let saved = read(&dir.join(STATE_FILE));
+        assert!(!saved.exercises["watching"].passed);
+        assert_eq!(saved.exercises["watching"].attempts, 2);
+        assert!(saved.verified(&running));
+        assert!(watch.verified(&running));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
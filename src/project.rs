@@ -1,3 +1,4 @@
+use crate::exercise::{Exercise, DEFAULT_EDITION};
 use glob::glob;
 use serde::{Deserialize, Serialize};
 use std::env;
@@ -38,16 +39,34 @@ impl RustAnalyzerProject {
         Ok(())
     }
 
-    /// If path contains .rs extension, add a crate to `rust-project.json`
-    fn path_to_json(&mut self, path: PathBuf) -> Result<(), Box<dyn Error>> {
+    /// If path contains .rs extension, add a crate to `rust-project.json`,
+    /// with the edition and cfg options of the exercise it belongs to
+    fn path_to_json(
+        &mut self,
+        path: PathBuf,
+        exercises: &[Exercise],
+    ) -> Result<(), Box<dyn Error>> {
         if let Some(ext) = path.extension() {
             if ext == "rs" {
+                // Only the root of an exercise that is a directory is a
+                // crate, the other files in it are its modules
+                let relative = path.strip_prefix(".").unwrap_or(&path);
+                if exercises.iter().any(|e| {
+                    e.path.is_dir() && relative.starts_with(&e.path) && relative != e.root()
+                }) {
+                    return Ok(());
+                }
+                let exercise = exercises.iter().find(|e| relative == e.root());
+                // This allows rust_analyzer to work inside #[test] blocks
+                let mut cfg = vec!["test".to_string()];
+                cfg.extend(exercise.into_iter().flat_map(|e| e.cfg.iter().cloned()));
                 self.crates.push(Crate {
                     root_module: path.display().to_string(),
-                    edition: "2021".to_string(),
+                    edition: exercise
+                        .map_or(DEFAULT_EDITION, Exercise::edition)
+                        .to_string(),
                     deps: Vec::new(),
-                    // This allows rust_analyzer to work inside #[test] blocks
-                    cfg: vec!["test".to_string()],
+                    cfg,
                 })
             }
         }
@@ -58,9 +77,9 @@ impl RustAnalyzerProject {
     /// Parse the exercises folder for .rs files, any matches will create
     /// a new `crate` in rust-project.json which allows rust-analyzer to
     /// treat it like a normal binary
-    pub fn exercises_to_json(&mut self) -> Result<(), Box<dyn Error>> {
+    pub fn exercises_to_json(&mut self, exercises: &[Exercise]) -> Result<(), Box<dyn Error>> {
         for path in glob("./exercises/**/*")? {
-            self.path_to_json(path?)?;
+            self.path_to_json(path?, exercises)?;
         }
         Ok(())
     }
@@ -86,7 +105,7 @@ impl RustAnalyzerProject {
 
         println!("Determined toolchain: {}\n", &toolchain);
 
-        self.sysroot_src = (std::path::Path::new(&*toolchain)
+        self.sysroot_src = (std::path::Path::new(toolchain)
             .join("lib")
             .join("rustlib")
             .join("src")
@@ -0,0 +1,323 @@
+use crate::grade::{ExerciseCheckList, ExerciseResult, Phase};
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+// The formats `--report` can write the graded results in
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum ReportFormat {
+    Json,
+    Junit,
+    Tap,
+}
+
+impl FromStr for ReportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(ReportFormat::Json),
+            "junit" => Ok(ReportFormat::Junit),
+            "tap" => Ok(ReportFormat::Tap),
+            _ => Err(format!("unknown format `{s}`, expected json, junit or tap")),
+        }
+    }
+}
+
+impl ReportFormat {
+    pub fn reporter(self) -> Box<dyn Reporter> {
+        match self {
+            ReportFormat::Json => Box::new(Json),
+            ReportFormat::Junit => Box::new(Junit),
+            ReportFormat::Tap => Box::new(Tap),
+        }
+    }
+
+    // Where the report goes when `--output` isn't given
+    pub fn default_output(self) -> PathBuf {
+        let extension = match self {
+            ReportFormat::Json => "json",
+            ReportFormat::Junit => "xml",
+            ReportFormat::Tap => "tap",
+        };
+        Path::new(".github/result/check_result").with_extension(extension)
+    }
+}
+
+// Renders the graded results for CI systems and other tools to read
+pub trait Reporter {
+    fn render(&self, results: &ExerciseCheckList) -> String;
+}
+
+// The check list as is, in the format the classroom reads
+pub struct Json;
+
+impl Reporter for Json {
+    fn render(&self, results: &ExerciseCheckList) -> String {
+        serde_json::to_string_pretty(results).unwrap()
+    }
+}
+
+// JUnit XML, with a test suite per category and a test case per exercise
+pub struct Junit;
+
+impl Reporter for Junit {
+    fn render(&self, results: &ExerciseCheckList) -> String {
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        let statistics = &results.statistics;
+        let _ = writeln!(
+            xml,
+            "<testsuites name=\"rustlings\" tests=\"{}\" failures=\"{}\" time=\"{}\">",
+            statistics.total_exercations,
+            statistics.total_failures,
+            seconds(statistics.total_time_ms),
+        );
+        for (category, exercises) in by_category(&results.exercises) {
+            let _ = writeln!(
+                xml,
+                "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" time=\"{}\">",
+                escape(category),
+                exercises.len(),
+                exercises.iter().filter(|e| !e.result).count(),
+                seconds(exercises.iter().map(|e| e.duration_ms).sum()),
+            );
+            for exercise in exercises {
+                let _ = write!(
+                    xml,
+                    "    <testcase name=\"{}\" classname=\"{}\" file=\"{}\" time=\"{}\"",
+                    escape(&exercise.name),
+                    escape(category),
+                    escape(&exercise.path.display().to_string()),
+                    seconds(exercise.duration_ms),
+                );
+                if exercise.result && exercise.output.is_none() {
+                    xml.push_str("/>\n");
+                    continue;
+                }
+                xml.push_str(">\n");
+                if exercise.result {
+                    let output = exercise.output.as_deref().unwrap_or_default();
+                    let _ = writeln!(xml, "      <system-out>{}</system-out>", escape(output));
+                } else {
+                    let _ = writeln!(
+                        xml,
+                        "      <failure type=\"{}\" message=\"{}\">{}</failure>",
+                        phase_name(exercise.phase),
+                        escape(failure_message(exercise.phase)),
+                        escape(&details(exercise)),
+                    );
+                }
+                xml.push_str("    </testcase>\n");
+            }
+            xml.push_str("  </testsuite>\n");
+        }
+        xml.push_str("</testsuites>\n");
+        xml
+    }
+}
+
+// The Test Anything Protocol, version 13, with a test point per exercise
+// and the diagnostics of a failed exercise in a YAML block
+pub struct Tap;
+
+impl Reporter for Tap {
+    fn render(&self, results: &ExerciseCheckList) -> String {
+        let mut tap = String::from("TAP version 13\n");
+        let _ = writeln!(tap, "1..{}", results.exercises.len());
+        for (number, exercise) in results.exercises.iter().enumerate() {
+            let ok = if exercise.result { "ok" } else { "not ok" };
+            let _ = writeln!(tap, "{ok} {} - {}", number + 1, exercise.name);
+            if exercise.result {
+                continue;
+            }
+            tap.push_str("  ---\n");
+            let _ = writeln!(tap, "  category: {}", quote(&exercise.category));
+            let _ = writeln!(tap, "  phase: {}", phase_name(exercise.phase));
+            let _ = writeln!(tap, "  message: {}", quote(failure_message(exercise.phase)));
+            let details = details(exercise);
+            if !details.is_empty() {
+                tap.push_str("  details: |\n");
+                for line in details.lines() {
+                    let _ = writeln!(tap, "    {line}");
+                }
+            }
+            tap.push_str("  ...\n");
+        }
+        tap
+    }
+}
+
+// Write a report of the results to `path`, creating its directory if needed
+pub fn write(format: ReportFormat, path: &Path, results: &ExerciseCheckList) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, format.reporter().render(results))
+}
+
+// The exercises grouped by category, in the order the categories first appear
+fn by_category(exercises: &[ExerciseResult]) -> Vec<(&str, Vec<&ExerciseResult>)> {
+    let mut categories: Vec<(&str, Vec<&ExerciseResult>)> = Vec::new();
+    for exercise in exercises {
+        match categories
+            .iter_mut()
+            .find(|(category, _)| *category == exercise.category)
+        {
+            Some((_, members)) => members.push(exercise),
+            None => categories.push((&exercise.category, vec![exercise])),
+        }
+    }
+    categories
+}
+
+// An exercise that failed without a phase passes, but `rustlings verify`
+// stopped at it because it is still marked as not done
+fn phase_name(phase: Option<Phase>) -> &'static str {
+    match phase {
+        None => "not_done",
+        Some(Phase::Compile) => "compile",
+        Some(Phase::Clippy) => "clippy",
+        Some(Phase::Test) => "test",
+        Some(Phase::Run) => "run",
+        Some(Phase::Output) => "output",
+        Some(Phase::Miri) => "miri",
+        Some(Phase::Tampered) => "tampered",
+        Some(Phase::Timeout) => "timeout",
+    }
+}
+
+fn failure_message(phase: Option<Phase>) -> &'static str {
+    match phase {
+        None => "The exercise is still marked as not done",
+        Some(Phase::Compile) => "The exercise didn't compile",
+        Some(Phase::Clippy) => "Clippy rejected the exercise",
+        Some(Phase::Test) => "The tests of the exercise failed",
+        Some(Phase::Run) => "The exercise exited with an error",
+        Some(Phase::Output) => "The exercise didn't print its expected output",
+        Some(Phase::Miri) => "Miri found undefined behavior or a memory leak",
+        Some(Phase::Tampered) => "The tests of the exercise were changed",
+        Some(Phase::Timeout) => "The exercise took too long",
+    }
+}
+
+// The diagnostics and the output of an exercise, whichever it has
+fn details(exercise: &ExerciseResult) -> String {
+    [&exercise.diagnostics, &exercise.output]
+        .into_iter()
+        .flatten()
+        .map(|text| text.trim_end())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn seconds(ms: u64) -> String {
+    format!("{:.3}", ms as f64 / 1000.0)
+}
+
+// Escape text for XML attributes and content. Control characters other
+// than tabs and newlines aren't allowed in XML 1.0 at all, so they are dropped.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if c.is_control() => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// A double-quoted YAML string. JSON strings are valid YAML.
+fn quote(text: &str) -> String {
+    serde_json::to_string(text).unwrap()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::exercise::Mode;
+    use crate::grade::check_list;
+    use std::time::Duration;
+
+    fn result(name: &str, category: &str, phase: Option<Phase>) -> ExerciseResult {
+        ExerciseResult {
+            name: name.to_string(),
+            result: phase.is_none(),
+            category: category.to_string(),
+            points: 1,
+            earned_points: if phase.is_none() { 1.0 } else { 0.0 },
+            mode: Mode::Compile,
+            path: PathBuf::from(format!("exercises/{category}/{name}.rs")),
+            phase,
+            duration_ms: 1500,
+            diagnostics: phase.map(|_| String::from("error[E0308]: mismatched types <&>")),
+            output: None,
+            tests: Vec::new(),
+            hints_revealed: 0,
+            tampered: false,
+        }
+    }
+
+    fn results() -> ExerciseCheckList {
+        check_list(
+            vec![
+                result("intro1", "intro", None),
+                result("vecs1", "vecs", Some(Phase::Compile)),
+                result("intro2", "intro", None),
+            ],
+            Duration::from_millis(4500),
+        )
+    }
+
+    #[test]
+    fn test_report_format() {
+        assert_eq!("junit".parse(), Ok(ReportFormat::Junit));
+        assert!("xml".parse::<ReportFormat>().is_err());
+        assert_eq!(
+            ReportFormat::Tap.default_output(),
+            Path::new(".github/result/check_result.tap")
+        );
+    }
+
+    #[test]
+    fn test_junit() {
+        let xml = Junit.render(&results());
+        assert!(xml
+            .contains("<testsuites name=\"rustlings\" tests=\"3\" failures=\"1\" time=\"4.500\">"));
+        assert!(
+            xml.contains("<testsuite name=\"intro\" tests=\"2\" failures=\"0\" time=\"3.000\">")
+        );
+        assert!(xml.contains("<testcase name=\"intro2\" classname=\"intro\" file=\"exercises/intro/intro2.rs\" time=\"1.500\"/>"));
+        assert!(xml.contains("<failure type=\"compile\" message=\"The exercise didn&apos;t compile\">error[E0308]: mismatched types &lt;&amp;&gt;</failure>"));
+        assert_eq!(xml.matches("<testsuite ").count(), 2);
+        assert_eq!(escape("a\u{1b}[31mb\n"), "a[31mb\n");
+    }
+
+    #[test]
+    fn test_tap() {
+        assert_eq!(
+            Tap.render(&results()),
+            "TAP version 13
+1..3
+ok 1 - intro1
+not ok 2 - vecs1
+  ---
+  category: \"vecs\"
+  phase: compile
+  message: \"The exercise didn't compile\"
+  details: |
+    error[E0308]: mismatched types <&>
+  ...
+ok 3 - intro2
+"
+        );
+    }
+}
@@ -1,73 +1,142 @@
-use std::process::Command;
+use std::io::{self, Write};
 
-use crate::exercise::{Exercise, Mode};
-use crate::verify::test;
+use crate::diff::output_diff;
+use crate::exercise::{CargoCommand, Exercise, Mode};
+use crate::originals::{restore, FileReset};
+use crate::verify::{print_compile_error, test, warn_timed_out};
 use indicatif::ProgressBar;
 
 // Invoke the rust compiler on the path of the given exercise,
 // and run the ensuing binary.
 // The verbose argument helps determine whether or not to show
 // the output from the test harnesses (if the mode of the exercise is test)
-pub fn run(exercise: &Exercise, verbose: bool) -> Result<(), ()> {
+pub fn run(exercise: &Exercise, verbose: bool, raw_diagnostics: bool) -> Result<(), ()> {
     match exercise.mode {
-        Mode::Test => test(exercise, verbose)?,
-        Mode::Compile => compile_and_run(exercise)?,
-        Mode::Clippy => compile_and_run(exercise)?,
-        Mode::BuildScript => test(exercise, verbose)?,
+        Mode::Test => test(exercise, verbose, raw_diagnostics)?,
+        Mode::Compile => compile_and_run(exercise, raw_diagnostics)?,
+        Mode::Clippy => compile_and_run(exercise, raw_diagnostics)?,
+        Mode::BuildScript => test(exercise, verbose, raw_diagnostics)?,
+        Mode::Cargo => match exercise.cargo_command() {
+            CargoCommand::Test => test(exercise, verbose, raw_diagnostics)?,
+            CargoCommand::Run | CargoCommand::Clippy => compile_and_run(exercise, raw_diagnostics)?,
+        },
     }
     Ok(())
 }
 
-// Resets the exercise by stashing the changes.
-pub fn reset(exercise: &Exercise) -> Result<(), ()> {
-    let command = Command::new("git")
-        .args(["stash", "--"])
-        .arg(&exercise.path)
-        .spawn();
+// Restore the given exercises from their pristine copies, after asking
+// for confirmation unless `yes` is set. The learner's versions are backed up.
+pub fn reset(exercises: &[&Exercise], yes: bool) -> Result<(), ()> {
+    if !yes && !confirm_reset(exercises) {
+        println!("{}", t!("run.nothing_reset"));
+        return Err(());
+    }
+
+    let mut result = Ok(());
+    for exercise in exercises {
+        match restore(exercise) {
+            Ok(files) => {
+                for (path, restored) in files {
+                    match restored {
+                        FileReset::Restored {
+                            backup: Some(backup),
+                        } => println!(
+                            "{}",
+                            t!(
+                                "run.reset_backed_up",
+                                path = path.display(),
+                                backup = backup.display()
+                            )
+                        ),
+                        FileReset::Restored { backup: None } => {
+                            println!("{}", t!("run.reset", path = path.display()))
+                        }
+                        FileReset::Unchanged => {
+                            println!("{}", t!("run.unchanged", path = path.display()))
+                        }
+                        FileReset::Removed { backup } => println!(
+                            "{}",
+                            t!(
+                                "run.removed",
+                                path = path.display(),
+                                backup = backup.display()
+                            )
+                        ),
+                    }
+                }
+            }
+            Err(e) => {
+                warn!("{}", t!("run.could_not_reset", exercise = exercise.name));
+                println!("{e}");
+                result = Err(());
+            }
+        }
+    }
+    result
+}
 
-    match command {
-        Ok(_) => Ok(()),
-        Err(_) => Err(()),
+fn confirm_reset(exercises: &[&Exercise]) -> bool {
+    match exercises {
+        [exercise] => print!("{}", t!("run.confirm_one", exercise = exercise)),
+        _ => print!("{}", t!("run.confirm_many", count = exercises.len())),
+    }
+    print!("{}", t!("run.backed_up"));
+    let _ = io::stdout().flush();
+    let mut answer = String::new();
+    if io::stdin().read_line(&mut answer).is_err() {
+        return false;
     }
+    matches!(answer.trim(), "y" | "Y" | "yes")
 }
 
 // Invoke the rust compiler on the path of the given exercise
 // and run the ensuing binary.
 // This is strictly for non-test binaries, so output is displayed
-fn compile_and_run(exercise: &Exercise) -> Result<(), ()> {
+fn compile_and_run(exercise: &Exercise, raw_diagnostics: bool) -> Result<(), ()> {
     let progress_bar = ProgressBar::new_spinner();
-    progress_bar.set_message(format!("Compiling {exercise}..."));
+    progress_bar.set_message(t!("verify.compiling", exercise = exercise));
     progress_bar.enable_steady_tick(100);
 
     let compilation_result = exercise.compile();
     let compilation = match compilation_result {
         Ok(compilation) => compilation,
+        Err(output) if output.timed_out => {
+            progress_bar.finish_and_clear();
+            warn_timed_out(exercise);
+            return Err(());
+        }
         Err(output) => {
             progress_bar.finish_and_clear();
-            warn!(
-                "Compilation of {} failed!, Compiler error message:\n",
-                exercise
-            );
-            println!("{}", output.stderr);
+            warn!("{}", t!("run.compile_failed", exercise = exercise));
+            print_compile_error(&output, raw_diagnostics);
             return Err(());
         }
     };
 
-    progress_bar.set_message(format!("Running {exercise}..."));
+    progress_bar.set_message(t!("verify.running", exercise = exercise));
     let result = compilation.run();
     progress_bar.finish_and_clear();
 
     match result {
         Ok(output) => {
             println!("{}", output.stdout);
-            success!("Successfully ran {}", exercise);
+            success!("{}", t!("run.ran", exercise = exercise));
             Ok(())
         }
+        Err(output) if output.unexpected_output => {
+            warn!("{}", t!("verify.wrong_output", exercise = exercise));
+            println!("{}", output_diff(exercise, &output.stdout));
+            Err(())
+        }
         Err(output) => {
             println!("{}", output.stdout);
             println!("{}", output.stderr);
 
-            warn!("Ran {} with errors", exercise);
+            if output.timed_out {
+                warn_timed_out(exercise);
+            } else {
+                warn!("{}", t!("verify.run_failed", exercise = exercise));
+            }
             Err(())
         }
     }
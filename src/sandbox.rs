@@ -0,0 +1,309 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+
+// The most memory a confined process may allocate
+const MEMORY_LIMIT_BYTES: u64 = 2 << 30;
+// The largest file a confined process may write
+const FILE_SIZE_LIMIT_BYTES: u64 = 256 << 20;
+
+// How a process that executes the learner's code is confined: the
+// exercise's binary or tests, and the build scripts Cargo runs for it.
+// It gets a private working directory and resource limits. On Linux it
+// also runs without network access and sees the project read-only, so it
+// can't tamper with the progress or the graded results.
+pub struct Sandbox {
+    // The private working directory, created if it doesn't exist
+    pub work_dir: PathBuf,
+    // Directories that stay writable, even if they are inside the project
+    pub writable: Vec<PathBuf>,
+    // The CPU time every process may use
+    pub cpu_secs: u64,
+}
+
+impl Sandbox {
+    pub fn confine(&self, command: &mut Command) -> io::Result<()> {
+        self.apply(command, namespaces().is_ok())
+    }
+
+    fn apply(&self, command: &mut Command, namespaces: bool) -> io::Result<()> {
+        fs::create_dir_all(&self.work_dir)?;
+        command.current_dir(&self.work_dir);
+        #[cfg(unix)]
+        unix::confine(command, self, namespaces)?;
+        #[cfg(not(unix))]
+        let _ = namespaces;
+        Ok(())
+    }
+}
+
+// Whether processes can be put into their own namespaces here. Containers
+// and some distributions don't allow unprivileged user namespaces, so this
+// is found out once by confining `rustlings --version`.
+fn namespaces() -> &'static Result<(), String> {
+    static NAMESPACES: OnceLock<Result<(), String>> = OnceLock::new();
+    NAMESPACES.get_or_init(|| {
+        if !cfg!(target_os = "linux") {
+            return Err(String::from("they are only available on Linux"));
+        }
+        let probe = Sandbox {
+            work_dir: env::temp_dir(),
+            writable: Vec::new(),
+            cpu_secs: 10,
+        };
+        let mut command = Command::new(env::current_exe().map_err(|e| e.to_string())?);
+        command
+            .arg("--version")
+            .stdout(Stdio::null())
+            .stderr(Stdio::null());
+        probe
+            .apply(&mut command, true)
+            .and_then(|_| command.status())
+            .map_err(|e| e.to_string())
+            .and_then(|status| match status.success() {
+                true => Ok(()),
+                false => Err(format!("a confined process failed with {status}")),
+            })
+    })
+}
+
+// Tell the learner, or the CI log, when exercises run less confined than
+// they would on a Linux with unprivileged namespaces
+pub fn warn_if_unconfined() {
+    if let Err(e) = namespaces() {
+        warn!("{}", t!("sandbox.unconfined", error = e));
+        println!("{}", t!("sandbox.limits_only"));
+    }
+}
+
+#[cfg(unix)]
+mod unix {
+    use super::{Sandbox, FILE_SIZE_LIMIT_BYTES, MEMORY_LIMIT_BYTES};
+    use std::io;
+    use std::os::unix::process::CommandExt;
+    use std::process::Command;
+
+    pub fn confine(command: &mut Command, sandbox: &Sandbox, namespaces: bool) -> io::Result<()> {
+        let limits = [
+            (libc::RLIMIT_DATA, MEMORY_LIMIT_BYTES),
+            (libc::RLIMIT_FSIZE, FILE_SIZE_LIMIT_BYTES),
+            (libc::RLIMIT_CPU, sandbox.cpu_secs),
+        ];
+        #[cfg(target_os = "linux")]
+        let namespaces = match namespaces {
+            true => Some(linux::Namespaces::new(sandbox)?),
+            false => None,
+        };
+        #[cfg(not(target_os = "linux"))]
+        let _ = namespaces;
+
+        // SAFETY: the closure only makes system calls on memory prepared
+        // before the fork, so it is safe to run between fork and exec.
+        unsafe {
+            command.pre_exec(move || {
+                for (resource, limit) in limits {
+                    set_limit(resource, limit)?;
+                }
+                #[cfg(target_os = "linux")]
+                if let Some(namespaces) = &namespaces {
+                    namespaces.enter()?;
+                }
+                Ok(())
+            });
+        }
+        Ok(())
+    }
+
+    #[cfg(target_os = "linux")]
+    type Resource = libc::__rlimit_resource_t;
+    #[cfg(not(target_os = "linux"))]
+    type Resource = libc::c_int;
+
+    // Lower a limit, keeping it if it already is lower
+    fn set_limit(resource: Resource, limit: u64) -> io::Result<()> {
+        let mut current = libc::rlimit {
+            rlim_cur: 0,
+            rlim_max: 0,
+        };
+        // SAFETY: `current` is a valid rlimit to write to
+        if unsafe { libc::getrlimit(resource, &mut current) } == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        let limit = limit as libc::rlim_t;
+        let lowered = libc::rlimit {
+            rlim_cur: current.rlim_cur.min(limit),
+            rlim_max: current.rlim_max.min(limit),
+        };
+        // SAFETY: `lowered` is a valid rlimit to read
+        if unsafe { libc::setrlimit(resource, &lowered) } == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    #[cfg(target_os = "linux")]
+    mod linux {
+        use super::Sandbox;
+        use std::env;
+        use std::ffi::{CStr, CString};
+        use std::io;
+        use std::os::unix::ffi::OsStrExt;
+        use std::path::Path;
+        use std::ptr;
+
+        // Everything entering the namespaces takes, prepared before the fork
+        pub struct Namespaces {
+            uid_map: String,
+            gid_map: String,
+            project: CString,
+            writable: Vec<CString>,
+        }
+
+        impl Namespaces {
+            pub fn new(sandbox: &Sandbox) -> io::Result<Namespaces> {
+                let project = env::current_dir()?;
+                // SAFETY: getuid and getgid always succeed
+                let (uid, gid) = unsafe { (libc::getuid(), libc::getgid()) };
+                Ok(Namespaces {
+                    uid_map: format!("{uid} {uid} 1"),
+                    gid_map: format!("{gid} {gid} 1"),
+                    project: c_path(&project)?,
+                    writable: sandbox
+                        .writable
+                        .iter()
+                        .filter(|dir| dir.starts_with(&project))
+                        .map(|dir| c_path(dir))
+                        .collect::<io::Result<_>>()?,
+                })
+            }
+
+            // Move into new user, mount and network namespaces. The new
+            // network namespace has no interfaces but a loopback that is
+            // down, and in the new mount namespace the project is
+            // read-only, except for the writable directories in it.
+            pub fn enter(&self) -> io::Result<()> {
+                // SAFETY: all pointers are to nul-terminated strings that
+                // outlive the calls, or null where the calls allow it.
+                unsafe {
+                    check(libc::unshare(
+                        libc::CLONE_NEWUSER | libc::CLONE_NEWNS | libc::CLONE_NEWNET,
+                    ))?;
+                    // Keep the same user and group inside the namespace, so
+                    // that files are still created as them
+                    write_file(c"/proc/self/setgroups", b"deny")?;
+                    write_file(c"/proc/self/uid_map", self.uid_map.as_bytes())?;
+                    write_file(c"/proc/self/gid_map", self.gid_map.as_bytes())?;
+
+                    // Don't let the mounts below leak out of the namespace
+                    check(libc::mount(
+                        ptr::null(),
+                        c"/".as_ptr(),
+                        ptr::null(),
+                        libc::MS_REC | libc::MS_PRIVATE,
+                        ptr::null(),
+                    ))?;
+                    bind(&self.project)?;
+                    remount_read_only(&self.project)?;
+                    for dir in &self.writable {
+                        bind(dir)?;
+                    }
+                }
+                Ok(())
+            }
+        }
+
+        fn c_path(path: &Path) -> io::Result<CString> {
+            CString::new(path.as_os_str().as_bytes())
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))
+        }
+
+        fn check(result: libc::c_int) -> io::Result<()> {
+            match result {
+                -1 => Err(io::Error::last_os_error()),
+                _ => Ok(()),
+            }
+        }
+
+        unsafe fn write_file(path: &CStr, contents: &[u8]) -> io::Result<()> {
+            let fd = libc::open(path.as_ptr(), libc::O_WRONLY | libc::O_CLOEXEC);
+            check(fd)?;
+            let written = libc::write(fd, contents.as_ptr().cast(), contents.len());
+            libc::close(fd);
+            match written {
+                -1 => Err(io::Error::last_os_error()),
+                n if n as usize != contents.len() => Err(io::Error::from_raw_os_error(libc::EIO)),
+                _ => Ok(()),
+            }
+        }
+
+        unsafe fn bind(dir: &CStr) -> io::Result<()> {
+            check(libc::mount(
+                dir.as_ptr(),
+                dir.as_ptr(),
+                ptr::null(),
+                libc::MS_BIND | libc::MS_REC,
+                ptr::null(),
+            ))
+        }
+
+        // A bind mount can only be remounted in a user namespace if the
+        // flags of the mount it came from are kept
+        unsafe fn remount_read_only(dir: &CStr) -> io::Result<()> {
+            let mut stat: libc::statvfs = std::mem::zeroed();
+            check(libc::statvfs(dir.as_ptr(), &mut stat))?;
+            let kept = [
+                (libc::ST_NOSUID, libc::MS_NOSUID),
+                (libc::ST_NODEV, libc::MS_NODEV),
+                (libc::ST_NOEXEC, libc::MS_NOEXEC),
+                (libc::ST_NOATIME, libc::MS_NOATIME),
+                (libc::ST_NODIRATIME, libc::MS_NODIRATIME),
+                (libc::ST_RELATIME, libc::MS_RELATIME),
+            ]
+            .iter()
+            .filter(|(st, _)| stat.f_flag & st != 0)
+            .fold(0, |flags, (_, ms)| flags | ms);
+            check(libc::mount(
+                ptr::null(),
+                dir.as_ptr(),
+                ptr::null(),
+                libc::MS_BIND | libc::MS_REMOUNT | libc::MS_RDONLY | kept,
+                ptr::null(),
+            ))
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod test {
+    use super::*;
+    use crate::process::output_with_timeout;
+    use std::time::Duration;
+
+    fn busy_loop(cpu_secs: u64, timeout: Duration) -> crate::process::Output {
+        let sandbox = Sandbox {
+            work_dir: env::temp_dir()
+                .join(format!("rustlings-sandbox-test-{}", std::process::id())),
+            writable: Vec::new(),
+            cpu_secs,
+        };
+        let mut command = Command::new("sh");
+        command.args(["-c", "while :; do :; done"]);
+        sandbox.apply(&mut command, false).unwrap();
+        let output = output_with_timeout(&mut command, timeout).unwrap();
+        let _ = fs::remove_dir_all(&sandbox.work_dir);
+        output
+    }
+
+    #[test]
+    fn test_busy_loops_time_out_before_the_cpu_limit() {
+        // The way exercises are confined, the timeout comes first
+        assert!(busy_loop(2, Duration::from_secs(1)).timed_out());
+        // But the CPU limit does stop a process that keeps going
+        let output = busy_loop(1, Duration::from_secs(10));
+        assert!(!output.timed_out());
+        assert!(!output.success());
+    }
+}
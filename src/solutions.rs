@@ -0,0 +1,138 @@
+use crate::exercise::Exercise;
+use crate::grade::{check, Phase};
+use crate::originals::original_path;
+use std::path::{Path, PathBuf};
+
+// The reference solutions, mirroring the layout of `exercises/`
+const SOLUTIONS_DIR: &str = "solutions";
+const EXERCISES_DIR: &str = "exercises";
+
+// Where the reference solution of an exercise lives
+pub fn solution_path(exercise: &Exercise) -> PathBuf {
+    let relative = exercise
+        .path
+        .strip_prefix(EXERCISES_DIR)
+        .unwrap_or(&exercise.path);
+    Path::new(SOLUTIONS_DIR).join(relative)
+}
+
+// Prove that every exercise can be solved with the current toolchain: its
+// solution has to pass and the exercise as shipped has to fail. Exercises
+// are checked from the pristine copies in `originals/`, so that versions
+// that were already worked on don't count. Exercises without one are a
+// problem too.
+pub fn check_solutions(exercises: &[Exercise]) -> Result<(), ()> {
+    if !Path::new(SOLUTIONS_DIR).is_dir() {
+        warn!("{}", t!("solutions.no_dir", dir = SOLUTIONS_DIR));
+        return Err(());
+    }
+
+    let mut problems = 0;
+    for exercise in exercises {
+        let name = &exercise.name;
+        let path = solution_path(exercise);
+        if !path.exists() {
+            warn!(
+                "{}",
+                t!(
+                    "solutions.no_solution",
+                    exercise = name,
+                    path = path.display()
+                )
+            );
+            problems += 1;
+            continue;
+        }
+        let solution = Exercise {
+            path,
+            ..exercise.clone()
+        };
+        let solved = check(&solution);
+        if let Some(phase) = solved.phase {
+            warn!("{}", describe(name, phase));
+            if let Some(diagnostics) = solved.diagnostics.or(solved.output) {
+                println!("{diagnostics}");
+            }
+            problems += 1;
+            continue;
+        }
+
+        // The learner may have worked on the exercise itself already
+        let original = original_path(&exercise.path);
+        if !original.exists() {
+            warn!(
+                "{}",
+                t!(
+                    "solutions.no_original",
+                    exercise = name,
+                    path = original.display()
+                )
+            );
+            problems += 1;
+            continue;
+        }
+        let starter = Exercise {
+            path: original,
+            ..exercise.clone()
+        };
+        if check(&starter).result {
+            warn!("{}", t!("solutions.already_passes", exercise = name));
+            problems += 1;
+            continue;
+        }
+        success!("{}", name);
+    }
+
+    if problems > 0 {
+        println!(
+            "{}",
+            t!(
+                "solutions.problems",
+                problems = problems,
+                total = exercises.len()
+            )
+        );
+        return Err(());
+    }
+    println!("{}", t!("solutions.all_good"));
+    Ok(())
+}
+
+// How the solution of an exercise fails
+fn describe(name: &str, phase: Phase) -> String {
+    match phase {
+        Phase::Compile => t!("solutions.fails_compile", exercise = name),
+        Phase::Clippy => t!("solutions.fails_clippy", exercise = name),
+        Phase::Test => t!("solutions.fails_tests", exercise = name),
+        Phase::Run => t!("solutions.fails_run", exercise = name),
+        Phase::Output => t!("solutions.fails_output", exercise = name),
+        Phase::Miri => t!("solutions.fails_miri", exercise = name),
+        Phase::Tampered => t!("solutions.fails_tampered", exercise = name),
+        Phase::Timeout => t!("solutions.fails_timeout", exercise = name),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::exercise::Mode;
+
+    #[test]
+    fn test_solution_path() {
+        let exercise = Exercise {
+            name: String::from("variables1"),
+            path: PathBuf::from("exercises/variables/variables1.rs"),
+            mode: Mode::Compile,
+            ..Default::default()
+        };
+        assert_eq!(
+            solution_path(&exercise),
+            Path::new("solutions/variables/variables1.rs")
+        );
+        let elsewhere = Exercise {
+            path: PathBuf::from("quiz1.rs"),
+            ..exercise
+        };
+        assert_eq!(solution_path(&elsewhere), Path::new("solutions/quiz1.rs"));
+    }
+}
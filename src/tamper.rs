@@ -0,0 +1,271 @@
+use crate::exercise::Exercise;
+use sha2::{Digest, Sha256};
+use std::fs;
+
+// Whether the tests of an exercise differ from the ones it was shipped
+// with, or the code around them redefines the macros they check with.
+// Only exercises with a `tests_sha256` are checked.
+pub fn tampered(exercise: &Exercise) -> bool {
+    match &exercise.tests_sha256 {
+        Some(expected) => {
+            tests_hash(exercise).as_ref() != Some(expected) || shadows_test_macros(exercise)
+        }
+        None => false,
+    }
+}
+
+// Whether a file of the exercise defines or imports a macro named like
+// `assert!` or `panic!`, which would turn the unchanged tests into no-ops
+fn shadows_test_macros(exercise: &Exercise) -> bool {
+    rust_files(exercise).any(|source| match fs::read_to_string(source) {
+        Ok(source) => shadowing_macros(&tokens(&source)),
+        Err(_) => true,
+    })
+}
+
+fn shadowing_macros(tokens: &[&str]) -> bool {
+    let shadows = |name: &&str| {
+        name.starts_with("assert") || name.starts_with("debug_assert") || *name == "panic"
+    };
+    tokens.windows(3).any(|window| match window {
+        ["macro_rules", "!", name] => shadows(name),
+        [_, "as", name] => shadows(name),
+        _ => false,
+    })
+}
+
+fn rust_files(exercise: &Exercise) -> impl Iterator<Item = std::path::PathBuf> {
+    exercise
+        .files()
+        .into_iter()
+        .filter(|file| file.extension().is_some_and(|ext| ext == "rs"))
+}
+
+// A SHA-256 hash of the `#[cfg(test)]` modules of the exercise, or None if
+// one of its files can't be read. Whitespace, comments and trailing commas
+// don't count, so reformatting the tests doesn't change the hash, but
+// removing or editing one does.
+pub fn tests_hash(exercise: &Exercise) -> Option<String> {
+    let mut hasher = Sha256::new();
+    for source in rust_files(exercise) {
+        let source = fs::read_to_string(source).ok()?;
+        for module in test_modules(&tokens(&source)) {
+            hasher.update(module.join(" "));
+            hasher.update("\n");
+        }
+    }
+    Some(
+        hasher
+            .finalize()
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect(),
+    )
+}
+
+// The tokens of every `#[cfg(test)] mod name { ... }` in the source
+fn test_modules<'a>(tokens: &[&'a str]) -> Vec<Vec<&'a str>> {
+    const CFG_TEST: &[&str] = &["#", "[", "cfg", "(", "test", ")", "]"];
+    let mut modules = Vec::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        if !tokens[i..].starts_with(CFG_TEST) {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        i += CFG_TEST.len();
+        // Other attributes and a visibility may come before `mod`
+        while i < tokens.len() && tokens[i] != "mod" && tokens[i] != "{" && tokens[i] != ";" {
+            i += 1;
+        }
+        if tokens.get(i) != Some(&"mod") || tokens.get(i + 2) != Some(&"{") {
+            continue;
+        }
+        i += 2;
+        let mut depth = 0;
+        while i < tokens.len() {
+            match tokens[i] {
+                "{" => depth += 1,
+                "}" => depth -= 1,
+                _ => {}
+            }
+            i += 1;
+            if depth == 0 {
+                break;
+            }
+        }
+        modules.push(tokens[start..i].to_vec());
+    }
+    modules
+}
+
+// Split Rust source into tokens, roughly: identifiers and numbers, string
+// and character literals, lifetimes and single punctuation characters.
+// Comments and trailing commas are left out.
+fn tokens(source: &str) -> Vec<&str> {
+    let bytes = source.as_bytes();
+    let mut tokens: Vec<&str> = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let start = i;
+        match bytes[i] {
+            b if b.is_ascii_whitespace() => {
+                i += 1;
+                continue;
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+                continue;
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                let mut depth = 0;
+                while i < bytes.len() {
+                    if bytes[i..].starts_with(b"/*") {
+                        depth += 1;
+                        i += 2;
+                    } else if bytes[i..].starts_with(b"*/") {
+                        depth -= 1;
+                        i += 2;
+                        if depth == 0 {
+                            break;
+                        }
+                    } else {
+                        i += 1;
+                    }
+                }
+                continue;
+            }
+            b'r' if raw_string_hashes(&bytes[i + 1..]).is_some() => {
+                let hashes = raw_string_hashes(&bytes[i + 1..]).unwrap_or(0);
+                i += hashes + 2;
+                while i < bytes.len() {
+                    if bytes[i] == b'"' && bytes[i + 1..].iter().take(hashes).all(|&b| b == b'#') {
+                        i += 1 + hashes;
+                        break;
+                    }
+                    i += 1;
+                }
+            }
+            b'"' => {
+                i += 1;
+                while i < bytes.len() && bytes[i] != b'"' {
+                    i += if bytes[i] == b'\\' { 2 } else { 1 };
+                }
+                i += 1;
+            }
+            b'\'' => {
+                let literal = match bytes.get(i + 1) {
+                    Some(b'\\') => true,
+                    _ => source[i + 1..]
+                        .chars()
+                        .nth(1)
+                        .is_some_and(|next| next == '\''),
+                };
+                i += 1;
+                if literal {
+                    while i < bytes.len() && bytes[i] != b'\'' {
+                        i += if bytes[i] == b'\\' { 2 } else { 1 };
+                    }
+                    i += 1;
+                } else {
+                    while i < bytes.len() && is_word(bytes[i]) {
+                        i += 1;
+                    }
+                }
+            }
+            b if is_word(b) => {
+                while i < bytes.len() && is_word(bytes[i]) {
+                    i += 1;
+                }
+            }
+            _ => {
+                i += source[i..].chars().next().map_or(1, char::len_utf8);
+            }
+        }
+        i = i.min(bytes.len());
+        while !source.is_char_boundary(i) {
+            i += 1;
+        }
+        let token = &source[start..i];
+        if matches!(token, ")" | "]" | "}") && tokens.last() == Some(&",") {
+            tokens.pop();
+        }
+        tokens.push(token);
+    }
+    tokens
+}
+
+// The number of `#`s if the bytes after an `r` start a raw string
+fn raw_string_hashes(bytes: &[u8]) -> Option<usize> {
+    let hashes = bytes.iter().take_while(|&&b| b == b'#').count();
+    (bytes.get(hashes) == Some(&b'"')).then_some(hashes)
+}
+
+fn is_word(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_' || !b.is_ascii()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn modules_of(source: &str) -> Vec<String> {
+        test_modules(&tokens(source))
+            .iter()
+            .map(|module| module.join(" "))
+            .collect()
+    }
+
+    #[test]
+    fn test_test_modules() {
+        let source = r#"
+fn add(a: u32, b: u32) -> u32 { a + b }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adds() {
+        // '{' and "}" don't confuse the braces
+        assert_eq!(add(1, 2), 3, "{}", '}');
+    }
+}
+"#;
+        assert_eq!(
+            modules_of(source),
+            [
+                "# [ cfg ( test ) ] mod tests { use super : : * ; # [ test ] fn adds ( ) { assert_eq ! ( add ( 1 , 2 ) , 3 , \"{}\" , '}' ) ; } }"
+            ]
+        );
+
+        let reformatted = source
+            .replace("3, \"{}\", '}');", "3, \"{}\", '}',);")
+            .replace("    ", "\t");
+        assert_eq!(modules_of(&reformatted), modules_of(source));
+        let edited = source.replace("3, \"{}\"", "4, \"{}\"");
+        assert_ne!(modules_of(&edited), modules_of(source));
+        assert!(modules_of(&source.replace("#[cfg(test)]", "")).is_empty());
+    }
+
+    #[test]
+    fn test_shadowing_macros() {
+        let source = "fn add(a: u32, b: u32) -> u32 { a + b }";
+        assert!(!shadowing_macros(&tokens(source)));
+        for shadowing in [
+            "macro_rules! assert_eq { ($($t:tt)*) => {} }",
+            "macro_rules! panic { ($($t:tt)*) => {} }",
+            "use std::println as assert;",
+        ] {
+            let source = format!("{shadowing}\n{source}");
+            assert!(shadowing_macros(&tokens(&source)), "{shadowing}");
+        }
+        // Using the macros is fine, only redefining them isn't
+        assert!(!shadowing_macros(&tokens(
+            "fn f() { assert_eq!(1, 1); panic!(); }"
+        )));
+    }
+}
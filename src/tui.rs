@@ -0,0 +1,585 @@
+use crate::cache::Cache;
+use crate::diff::output_diff;
+use crate::exercise::{Attempt, CargoCommand, Exercise, MiriOutcome, Mode, State};
+use crate::graph::{self, locked_by};
+use crate::progress::Progress;
+use crate::verify::{
+    attempt, compile_error, last_error_code, miri_unavailable_advice, record, test_breakdown,
+    timed_out_advice,
+};
+use crate::WatchStatus;
+use notify::{DebouncedEvent, RecommendedWatcher, RecursiveMode, Watcher};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Style, Stylize};
+use ratatui::text::{Line, Text};
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::Frame;
+use std::ffi::OsStr;
+use std::path::Path;
+use std::process::Command;
+use std::sync::mpsc::{channel, Sender};
+use std::thread;
+use std::time::Duration;
+
+// The outcome of verifying an exercise, ready to be shown
+struct Outcome {
+    passed: bool,
+    // Whether the outcome was taken from the cache
+    cached: bool,
+    header: String,
+    text: String,
+}
+
+// Watch mode as a full-screen terminal interface. Exercises are verified
+// on a worker thread, so that the interface stays responsive while they
+// compile. Like the line-based watch mode, it goes through the exercises
+// with every one after its prerequisites.
+pub fn watch(
+    exercises: &[Exercise],
+    verbose: bool,
+    success_hints: bool,
+    raw_diagnostics: bool,
+    cache: &Cache,
+    progress: &mut Progress,
+) -> notify::Result<WatchStatus> {
+    let (tx, rx) = channel();
+    let mut watcher: RecommendedWatcher = Watcher::new(tx, Duration::from_secs(1))?;
+    watcher.watch(Path::new("./exercises"), RecursiveMode::Recursive)?;
+
+    let ordered = graph::order(exercises);
+    let ordered = ordered.as_slice();
+    let mut terminal = ratatui::init();
+    let status = thread::scope(|s| {
+        let (job_tx, job_rx) = channel::<(usize, bool)>();
+        let (result_tx, result_rx) = channel();
+        s.spawn(move || {
+            for (index, use_cache) in job_rx {
+                let outcome = check(
+                    ordered[index],
+                    cache,
+                    verbose,
+                    success_hints,
+                    raw_diagnostics,
+                    use_cache,
+                );
+                if result_tx.send((index, outcome)).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut app = App::new(exercises, ordered, progress, job_tx);
+        app.advance();
+        loop {
+            terminal.draw(|frame| app.draw(frame))?;
+            while let Ok((index, outcome)) = result_rx.try_recv() {
+                app.finish(index, outcome);
+            }
+            while let Ok(event) = rx.try_recv() {
+                if let DebouncedEvent::Create(path)
+                | DebouncedEvent::Chmod(path)
+                | DebouncedEvent::Write(path) = event
+                {
+                    if path.extension() == Some(OsStr::new("rs")) && path.exists() {
+                        app.file_changed(&path);
+                    }
+                }
+            }
+            if event::poll(Duration::from_millis(100))? {
+                if let Event::Key(key) = event::read()? {
+                    if key.kind == KeyEventKind::Press && !app.handle_key(key) {
+                        break;
+                    }
+                }
+            }
+        }
+        // Leave the screen right away, even if the worker still has to
+        // finish compiling an exercise
+        ratatui::restore();
+        Ok(app.status())
+    });
+    ratatui::restore();
+    status.map_err(notify::Error::Io)
+}
+
+struct App<'a, 'p> {
+    // All exercises in the order of info.toml, and the ones shown in the
+    // order they are done in, which every index below refers to
+    all: &'a [Exercise],
+    exercises: &'a [&'a Exercise],
+    progress: &'p mut Progress,
+    jobs: Sender<(usize, bool)>,
+    // Whether each exercise is done, refreshed whenever it may have changed
+    done: Vec<bool>,
+    // Whether each exercise failed the last time it was verified
+    failed: Vec<bool>,
+    // The exercise being worked on, and the outcome of its last verification
+    current: usize,
+    outcome: Option<Outcome>,
+    // The exercise being verified right now
+    running: Option<usize>,
+    // Move on to the next pending exercise once the running one passes
+    auto_advance: bool,
+    list: ListState,
+    show_list: bool,
+    show_hint: bool,
+    scroll: u16,
+}
+
+impl<'a, 'p> App<'a, 'p> {
+    fn new(
+        all: &'a [Exercise],
+        exercises: &'a [&'a Exercise],
+        progress: &'p mut Progress,
+        jobs: Sender<(usize, bool)>,
+    ) -> Self {
+        let done = exercises.iter().map(|e| progress.is_done(e)).collect();
+        App {
+            all,
+            exercises,
+            progress,
+            jobs,
+            done,
+            failed: vec![false; exercises.len()],
+            current: 0,
+            outcome: None,
+            running: None,
+            auto_advance: false,
+            list: ListState::default(),
+            show_list: true,
+            show_hint: false,
+            scroll: 0,
+        }
+    }
+
+    fn status(&self) -> WatchStatus {
+        if self.done.iter().all(|&done| done) {
+            WatchStatus::Finished
+        } else {
+            WatchStatus::Unfinished
+        }
+    }
+
+    // Make an exercise the current one and verify it
+    fn select(&mut self, index: usize, use_cache: bool) {
+        if index != self.current {
+            self.outcome = None;
+            self.scroll = 0;
+        }
+        self.current = index;
+        self.list.select(Some(index));
+        self.running = Some(index);
+        let _ = self.jobs.send((index, use_cache));
+    }
+
+    // Whether the exercise isn't done yet and its prerequisites are
+    fn available(&self, index: usize) -> bool {
+        !self.done[index] && !self.locked(index)
+    }
+
+    fn locked(&self, index: usize) -> bool {
+        !locked_by(self.exercises[index], self.all, self.progress).is_empty()
+    }
+
+    // Go to the first exercise that isn't done yet and isn't locked
+    fn advance(&mut self) {
+        let next = graph::next(self.all, self.progress)
+            .and_then(|next| self.exercises.iter().position(|e| e.name == next.name));
+        match next {
+            Some(index) => {
+                self.auto_advance = true;
+                self.select(index, true);
+            }
+            None => {
+                self.auto_advance = false;
+                self.outcome = None;
+            }
+        }
+    }
+
+    // Go to the next exercise after the current one that isn't done yet
+    // and isn't locked
+    fn next(&mut self) {
+        let len = self.exercises.len();
+        let next = (1..=len)
+            .map(|offset| (self.current + offset) % len)
+            .find(|&i| self.available(i));
+        if let Some(index) = next {
+            self.auto_advance = false;
+            self.select(index, true);
+        }
+    }
+
+    fn finish(&mut self, index: usize, outcome: Outcome) {
+        let exercise = self.exercises[index];
+        record(exercise, outcome.passed, outcome.cached, self.progress);
+        self.done[index] = self.progress.is_done(exercise);
+        self.failed[index] = !outcome.passed;
+        if self.running == Some(index) {
+            self.running = None;
+        }
+        let passed = outcome.passed;
+        if index == self.current {
+            self.outcome = Some(outcome);
+            self.scroll = 0;
+        }
+        if self.auto_advance && passed && self.done[index] {
+            self.advance();
+        } else if self.running.is_none() {
+            self.auto_advance = false;
+        }
+    }
+
+    fn file_changed(&mut self, path: &Path) {
+        let Ok(path) = path.canonicalize() else {
+            return;
+        };
+        if let Some(index) = self.exercises.iter().position(|e| e.owns(&path)) {
+            self.done[index] = self.progress.is_done(self.exercises[index]);
+            self.auto_advance = true;
+            self.select(index, true);
+        }
+    }
+
+    // Handle a key press. Returns false once the user wants to quit.
+    fn handle_key(&mut self, key: KeyEvent) -> bool {
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return false,
+            KeyCode::Char('n') => self.next(),
+            KeyCode::Char('h') => {
+                // The hint pane opens with the hints revealed so far, and
+                // further presses reveal the next one
+                let exercise = self.exercises[self.current];
+                if self.show_hint || self.progress.hints_revealed(exercise) == 0 {
+                    self.progress.reveal_hint(exercise);
+                }
+                self.show_hint = true;
+            }
+            KeyCode::Char('H') => self.show_hint = false,
+            KeyCode::Char('r') => {
+                self.auto_advance = false;
+                self.select(self.current, false);
+            }
+            KeyCode::Char('l') => self.show_list = !self.show_list,
+            KeyCode::Char('e') => self.explain(),
+            KeyCode::Up | KeyCode::Char('k') if self.show_list => self.list.select_previous(),
+            KeyCode::Down | KeyCode::Char('j') if self.show_list => self.list.select_next(),
+            KeyCode::Enter if self.show_list => {
+                if let Some(index) = self.list.selected() {
+                    self.auto_advance = false;
+                    self.select(index.min(self.exercises.len() - 1), true);
+                }
+            }
+            KeyCode::Up | KeyCode::Char('k') => self.scroll = self.scroll.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => self.scroll = self.scroll.saturating_add(1),
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(10),
+            KeyCode::PageDown => self.scroll = self.scroll.saturating_add(10),
+            _ => {}
+        }
+        true
+    }
+
+    // Show what `rustc --explain` has to say about the last compiler error
+    fn explain(&mut self) {
+        let outcome = match last_error_code() {
+            Some(code) => match Command::new("rustc").args(["--explain", &code]).output() {
+                Ok(output) => Outcome {
+                    passed: false,
+                    cached: false,
+                    header: format!("rustc --explain {code}"),
+                    text: String::from_utf8_lossy(&output.stdout).into_owned(),
+                },
+                Err(e) => Outcome {
+                    passed: false,
+                    cached: false,
+                    header: t!("watch.explain_failed", code = code, error = e),
+                    text: String::new(),
+                },
+            },
+            None => Outcome {
+                passed: false,
+                cached: false,
+                header: t!("watch.nothing_to_explain"),
+                text: String::new(),
+            },
+        };
+        self.outcome = Some(outcome);
+        self.scroll = 0;
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let hint_height = if self.show_hint { 8 } else { 0 };
+        let [main, hint, keys] = Layout::vertical([
+            Constraint::Min(3),
+            Constraint::Length(hint_height),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+        let list_width = if self.show_list { 30 } else { 0 };
+        let [list, output] =
+            Layout::horizontal([Constraint::Length(list_width), Constraint::Min(10)]).areas(main);
+
+        if self.show_list {
+            self.draw_list(frame, list);
+        }
+        self.draw_output(frame, output);
+        if self.show_hint {
+            self.draw_hint(frame, hint);
+        }
+
+        let num_done = self.done.iter().filter(|&&done| done).count();
+        let [keys_area, progress_area] =
+            Layout::horizontal([Constraint::Min(0), Constraint::Length(24)]).areas(keys);
+        frame.render_widget(Line::from(t!("tui.keys")).reversed(), keys_area);
+        frame.render_widget(
+            Line::from(t!(
+                "tui.done",
+                done = num_done,
+                total = self.exercises.len()
+            ))
+            .right_aligned()
+            .reversed(),
+            progress_area,
+        );
+    }
+
+    fn draw_list(&mut self, frame: &mut Frame, area: Rect) {
+        let items: Vec<ListItem> = self
+            .exercises
+            .iter()
+            .enumerate()
+            .map(|(i, exercise)| {
+                let icon = if self.running == Some(i) {
+                    "…".yellow()
+                } else if self.done[i] {
+                    "✓".green()
+                } else if self.failed[i] {
+                    "✗".red()
+                } else if self.locked(i) {
+                    "⊘".dark_gray()
+                } else {
+                    "•".dark_gray()
+                };
+                let name = if i == self.current {
+                    exercise.name.as_str().bold()
+                } else {
+                    exercise.name.as_str().into()
+                };
+                ListItem::new(Line::from(vec![icon, " ".into(), name]))
+            })
+            .collect();
+        let list = List::new(items)
+            .block(Block::bordered().title(t!("tui.exercises")))
+            .highlight_style(Style::new().reversed());
+        frame.render_stateful_widget(list, area, &mut self.list);
+    }
+
+    fn draw_hint(&self, frame: &mut Frame, area: Rect) {
+        let exercise = self.exercises[self.current];
+        let hints = exercise.hints();
+        let revealed = self.progress.hints_revealed(exercise).min(hints.len());
+        let mut text = Text::default();
+        if hints.is_empty() {
+            text.push_line(t!("tui.no_hints"));
+        }
+        for hint in &hints[..revealed] {
+            for line in hint.lines() {
+                text.push_line(line.to_string());
+            }
+        }
+        if revealed < hints.len() {
+            if self.progress.hint_unlocked(exercise) {
+                text.push_line(t!("tui.next_hint").dark_gray());
+            } else {
+                text.push_line(t!("hint.locked").dark_gray());
+            }
+        }
+        let paragraph = Paragraph::new(text)
+            .block(Block::bordered().title(t!(
+                "tui.hint_title",
+                revealed = revealed,
+                total = hints.len()
+            )))
+            .wrap(Wrap { trim: false });
+        frame.render_widget(paragraph, area);
+    }
+
+    fn draw_output(&self, frame: &mut Frame, area: Rect) {
+        let exercise = self.exercises[self.current];
+        let mut text = Text::default();
+        if self.running == Some(self.current) {
+            text.push_line(t!("tui.verifying", exercise = exercise).yellow());
+        } else if let Some(outcome) = &self.outcome {
+            let header = if outcome.passed {
+                outcome.header.as_str().green().bold()
+            } else {
+                outcome.header.as_str().red().bold()
+            };
+            text.push_line(header);
+            text.push_line("");
+            for line in console::strip_ansi_codes(&outcome.text).lines() {
+                text.push_line(line.to_string());
+            }
+        } else if self.done.iter().all(|&done| done) {
+            text.push_line(t!("tui.all_done").green().bold());
+        }
+        let paragraph = Paragraph::new(text)
+            .block(Block::bordered().title(format!(" {exercise} ")))
+            .wrap(Wrap { trim: false })
+            .scroll((self.scroll, 0));
+        frame.render_widget(paragraph, area);
+    }
+}
+
+// Verify an exercise the way `verify` does, but collect what it would print
+fn check(
+    exercise: &Exercise,
+    cache: &Cache,
+    verbose: bool,
+    success_hints: bool,
+    raw_diagnostics: bool,
+    use_cache: bool,
+) -> Outcome {
+    let result = match attempt(exercise, cache, use_cache) {
+        Attempt::Cached(output) => {
+            return passed(exercise, true, output.unwrap_or_default(), success_hints);
+        }
+        Attempt::CompileFailed(output) if output.timed_out => {
+            return failed(
+                t!("verify.timed_out", exercise = exercise),
+                timed_out_advice(exercise),
+            );
+        }
+        Attempt::CompileFailed(output) => {
+            return failed(
+                t!("tui.compile_failed", exercise = exercise),
+                compile_error(&output, raw_diagnostics),
+            );
+        }
+        Attempt::Ran(result) => result,
+    };
+
+    match result {
+        Ok(_) if exercise.only_linted() => passed(exercise, false, String::new(), success_hints),
+        Ok(output) => {
+            let text = match (exercise.mode, exercise.cargo_command()) {
+                (Mode::Compile, _) | (Mode::Cargo, CargoCommand::Run) => output.stdout,
+                _ if verbose => format!("{}\n{}", test_breakdown(&output.stdout), output.stdout),
+                _ => test_breakdown(&output.stdout),
+            };
+            let text = match output.miri {
+                MiriOutcome::Unavailable => {
+                    format!("{text}\n{}", miri_unavailable_advice(exercise))
+                }
+                _ => text,
+            };
+            passed(exercise, false, text, success_hints)
+        }
+        Err(output) => {
+            if output.timed_out {
+                failed(
+                    t!("verify.timed_out", exercise = exercise),
+                    format!("{}\n\n{}", timed_out_advice(exercise), output.stdout),
+                )
+            } else if output.miri == MiriOutcome::Failed {
+                failed(t!("tui.miri_failed", exercise = exercise), output.stderr)
+            } else if output.unexpected_output {
+                failed(
+                    t!("verify.wrong_output", exercise = exercise),
+                    output_diff(exercise, &output.stdout),
+                )
+            } else if let (Mode::Compile, _) | (Mode::Cargo, CargoCommand::Run) =
+                (exercise.mode, exercise.cargo_command())
+            {
+                failed(
+                    t!("verify.run_failed", exercise = exercise),
+                    format!("{}\n{}", output.stdout, output.stderr),
+                )
+            } else {
+                failed(
+                    t!("tui.test_failed", exercise = exercise),
+                    format!("{}\n{}", test_breakdown(&output.stdout), output.stdout),
+                )
+            }
+        }
+    }
+}
+
+fn passed(exercise: &Exercise, cached: bool, output: String, success_hints: bool) -> Outcome {
+    let header = match (exercise.mode, exercise.cargo_command()) {
+        (Mode::Compile, _) | (Mode::Cargo, CargoCommand::Run) => {
+            t!("verify.ran", exercise = exercise)
+        }
+        (Mode::Test, _) | (Mode::Cargo, CargoCommand::Test) => {
+            t!("verify.tested", exercise = exercise)
+        }
+        (Mode::Clippy | Mode::BuildScript, _) | (Mode::Cargo, CargoCommand::Clippy) => {
+            t!("verify.compiled", exercise = exercise)
+        }
+    };
+    let text = match exercise.state() {
+        State::Done => output,
+        State::Pending(_) if success_hints => format!(
+            "{output}\n{}\n{}\n\n{}",
+            t!("verify.hints"),
+            exercise.hints().join("\n\n"),
+            t!("tui.keep_working")
+        ),
+        State::Pending(_) => format!("{output}\n{}", t!("tui.keep_working")),
+    };
+    Outcome {
+        passed: true,
+        cached,
+        header,
+        text,
+    }
+}
+
+fn failed(header: String, text: String) -> Outcome {
+    Outcome {
+        passed: false,
+        cached: false,
+        header,
+        text,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_exercises_follow_their_prerequisites() {
+        let exercise = |name: &str, requires: &[&str]| Exercise {
+            name: name.to_string(),
+            path: PathBuf::from("tests/fixture/state/pending_exercise.rs"),
+            mode: Mode::Compile,
+            requires: requires.iter().map(|r| r.to_string()).collect(),
+            ..Default::default()
+        };
+        let exercises = [
+            exercise("a", &["c"]),
+            exercise("b", &[]),
+            exercise("c", &[]),
+        ];
+        let ordered = graph::order(&exercises);
+        let mut progress = Progress::default();
+        let (jobs, job_rx) = channel();
+        let mut app = App::new(&exercises, &ordered, &mut progress, jobs);
+
+        let names =
+            |app: &App| -> Vec<String> { app.exercises.iter().map(|e| e.name.clone()).collect() };
+        assert_eq!(names(&app), ["b", "c", "a"]);
+        app.advance();
+        assert_eq!(app.exercises[app.current].name, "b");
+        // `a` is locked until `c` is done, so `n` never offers it
+        app.next();
+        assert_eq!(app.exercises[app.current].name, "c");
+        app.next();
+        assert_eq!(app.exercises[app.current].name, "b");
+        assert_eq!(job_rx.try_iter().count(), 3);
+    }
+}
@@ -0,0 +1,405 @@
+use crate::exercise::{files_below, CargoCommand, Exercise, ExerciseList, ExpectedOutput, Mode};
+use crate::graph;
+use crate::i18n::{Localized, INFO_LOCALES};
+use crate::originals::original_below;
+use crate::tamper::tests_hash;
+use regex::Regex;
+use std::collections::HashSet;
+use std::fs;
+use std::iter;
+use std::path::Path;
+
+const MODES: &[&str] = &["compile", "test", "clippy", "buildscript", "cargo"];
+const DEPENDENCY_TABLES: &[&str] = &["dependencies", "dev-dependencies", "build-dependencies"];
+const EDITIONS: &[&str] = &["2015", "2018", "2021", "2024"];
+
+// Check `info.toml` and the exercises tree for mistakes that would
+// otherwise only show up as a panic or a silently ignored exercise.
+// Returns a description of every problem that was found.
+pub fn check(info: &str, exercises_dir: &Path) -> Vec<String> {
+    let table: toml::Value = match toml::from_str(info) {
+        Ok(table) => table,
+        Err(e) => return vec![t!("validate.not_toml", error = e)],
+    };
+
+    // Unknown modes are reported before deserializing, so that the
+    // exercise they belong to can be named
+    let mut problems = Vec::new();
+    let entries = table.get("exercises").and_then(|e| e.as_array());
+    for (i, entry) in entries.into_iter().flatten().enumerate() {
+        let name = entry
+            .get("name")
+            .and_then(|n| n.as_str())
+            .map_or_else(|| t!("validate.nameless", number = i + 1), String::from);
+        if let Some(mode) = entry.get("mode").and_then(|m| m.as_str()) {
+            if !MODES.contains(&mode) {
+                problems.push(t!(
+                    "validate.unknown_mode",
+                    exercise = name,
+                    mode = mode,
+                    modes = MODES.join(", ")
+                ));
+            }
+        }
+    }
+    if !problems.is_empty() {
+        return problems;
+    }
+    let (exercises, editable_tests) = match toml::from_str::<ExerciseList>(info) {
+        Ok(list) => {
+            let editable_tests = list.editable_tests.clone();
+            (list.into_exercises(), editable_tests)
+        }
+        Err(e) => return vec![t!("main.invalid_info", error = e)],
+    };
+
+    let mut names = HashSet::new();
+    let mut inputs = HashSet::new();
+    for exercise in &exercises {
+        let name = &exercise.name;
+        if !names.insert(name.as_str()) {
+            problems.push(t!("validate.duplicate", exercise = name));
+        }
+        if exercise.hints().is_empty() {
+            problems.push(t!("validate.no_hint", exercise = name));
+        }
+        let per_locale = iter::once(&exercise.hint)
+            .chain(&exercise.hints)
+            .filter_map(Localized::locales);
+        for locales in per_locale {
+            if !locales.contains(&"en") {
+                problems.push(t!("validate.no_english_hint", exercise = name));
+            }
+            for locale in locales.iter().filter(|l| !INFO_LOCALES.contains(l)) {
+                problems.push(t!(
+                    "validate.unknown_locale",
+                    exercise = name,
+                    locale = locale,
+                    locales = INFO_LOCALES.join(", ")
+                ));
+            }
+        }
+        if !EDITIONS.contains(&exercise.edition()) {
+            problems.push(t!(
+                "validate.unknown_edition",
+                exercise = name,
+                edition = exercise.edition(),
+                editions = EDITIONS.join(", ")
+            ));
+        }
+        inputs.extend(exercise.inputs());
+        let runs_tests = match exercise.mode {
+            Mode::Test => true,
+            Mode::Cargo => exercise.cargo_command() == CargoCommand::Test,
+            Mode::Compile | Mode::Clippy | Mode::BuildScript => false,
+        };
+        let runs_binary = match exercise.mode {
+            Mode::Compile => true,
+            Mode::Cargo => !runs_tests,
+            Mode::Test | Mode::Clippy | Mode::BuildScript => false,
+        };
+        if exercise.miri && !runs_tests {
+            problems.push(t!("validate.miri_without_tests", exercise = name));
+        }
+        match &exercise.expected_output {
+            Some(_) if !runs_binary => {
+                problems.push(t!("validate.expected_output_mode", exercise = name));
+            }
+            Some(ExpectedOutput::Regex(pattern)) => {
+                if let Err(e) = Regex::new(pattern) {
+                    problems.push(t!("validate.invalid_regex", exercise = name, error = e));
+                }
+            }
+            _ => {}
+        }
+
+        if !exercise.path.exists() {
+            problems.push(t!(
+                "validate.missing",
+                exercise = name,
+                path = exercise.path.display()
+            ));
+            continue;
+        }
+        // Without a pristine copy, the exercise can't be reset
+        for file in exercise.files() {
+            let original = original_below(exercises_dir, &file);
+            if !original.exists() {
+                problems.push(t!(
+                    "validate.no_original",
+                    exercise = name,
+                    file = file.display(),
+                    path = original.display()
+                ));
+            }
+        }
+        if let Mode::Cargo = exercise.mode {
+            if let Err(problem) = check_manifest(exercise) {
+                problems.push(problem);
+                continue;
+            }
+        }
+        let root = exercise.root();
+        if exercise.path.is_dir() {
+            match exercise.mode {
+                _ if !root.exists() => {
+                    problems.push(t!(
+                        "validate.no_root",
+                        exercise = name,
+                        path = parent(&root).display()
+                    ));
+                    continue;
+                }
+                Mode::Compile | Mode::Clippy | Mode::Cargo
+                    if !runs_tests && !root.ends_with("main.rs") =>
+                {
+                    problems.push(t!(
+                        "validate.no_main",
+                        exercise = name,
+                        path = parent(&root).display()
+                    ));
+                }
+                _ => {}
+            }
+        }
+        match (&exercise.tests_sha256, tests_hash(exercise)) {
+            (Some(_), _) if !runs_tests => {
+                problems.push(t!("validate.hash_without_tests", exercise = name));
+            }
+            (Some(expected), Some(hash)) if *expected != hash => {
+                problems.push(t!("validate.hash_mismatch", exercise = name, hash = hash));
+            }
+            // Otherwise, deleting the hash would quietly turn off the check
+            (None, Some(hash)) if runs_tests && !editable_tests.contains(name) => {
+                problems.push(t!("validate.no_hash", exercise = name, hash = hash));
+            }
+            _ => {}
+        }
+        let has_tests = exercise
+            .files()
+            .iter()
+            .filter_map(|file| fs::read_to_string(file).ok())
+            .any(|source| source.contains("#[test]"));
+        match exercise.mode {
+            _ if runs_tests && !has_tests => {
+                problems.push(t!("validate.no_tests", exercise = name));
+            }
+            Mode::Clippy if !in_directory(&exercise.path, "clippy") => {
+                problems.push(t!(
+                    "validate.clippy_directory",
+                    exercise = name,
+                    path = parent(&exercise.path).display()
+                ));
+            }
+            Mode::BuildScript if !root.with_file_name("build.rs").exists() => {
+                problems.push(t!(
+                    "validate.no_build_script",
+                    exercise = name,
+                    path = parent(&root).display()
+                ));
+            }
+            _ => {}
+        }
+    }
+
+    for name in &editable_tests {
+        if !names.contains(name.as_str()) {
+            problems.push(t!("validate.unknown_editable", exercise = name));
+        }
+    }
+
+    if let Err(e) = graph::validate(&exercises) {
+        problems.push(e);
+    }
+
+    let sources = files_below(exercises_dir)
+        .into_iter()
+        .filter(|file| file.extension().is_some_and(|ext| ext == "rs"));
+    for source in sources {
+        if !inputs.contains(&source) {
+            problems.push(t!("validate.orphan", path = source.display()));
+        }
+    }
+    problems
+}
+
+// A Cargo exercise has to be a package whose dependencies can be built
+// offline, which means they all have to be given by path
+fn check_manifest(exercise: &Exercise) -> Result<(), String> {
+    let path = exercise.path.join("Cargo.toml");
+    let Ok(manifest) = fs::read_to_string(&path) else {
+        return Err(t!(
+            "validate.no_manifest",
+            exercise = exercise.name,
+            path = path.display()
+        ));
+    };
+    let manifest: toml::Value = toml::from_str(&manifest).map_err(|e| {
+        t!(
+            "validate.manifest_not_toml",
+            exercise = exercise.name,
+            path = path.display(),
+            error = e
+        )
+    })?;
+    for table in DEPENDENCY_TABLES {
+        let dependencies = manifest.get(table).and_then(|t| t.as_table());
+        for (dependency, spec) in dependencies.into_iter().flatten() {
+            if spec.get("path").is_none() {
+                return Err(t!(
+                    "validate.remote_dependency",
+                    exercise = exercise.name,
+                    dependency = dependency,
+                    path = path.display()
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+// Check `info.toml` and the exercises tree, and print every problem found
+pub fn validate(info: &str) -> Result<(), ()> {
+    let problems = check(info, Path::new("exercises"));
+    if problems.is_empty() {
+        success!("{}", t!("validate.valid"));
+        return Ok(());
+    }
+    for problem in &problems {
+        warn!("{}", problem);
+    }
+    println!("{}", t!("validate.problems", problems = problems.len()));
+    Err(())
+}
+
+fn parent(path: &Path) -> &Path {
+    path.parent().unwrap_or(Path::new("."))
+}
+
+fn in_directory(path: &Path, dir: &str) -> bool {
+    parent(path).file_name().is_some_and(|name| name == dir)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn test_check() {
+        let root = env::temp_dir().join(format!("rustlings-validate-test-{}", std::process::id()));
+        let dir = root.join("exercises");
+        fs::create_dir_all(dir.join("clippy")).unwrap();
+        fs::write(dir.join("good.rs"), "#[test]\nfn works() {}\n").unwrap();
+        fs::write(dir.join("untested.rs"), "fn main() {}\n").unwrap();
+        fs::write(dir.join("linted.rs"), "fn main() {}\n").unwrap();
+        fs::write(dir.join("orphan.rs"), "fn main() {}\n").unwrap();
+        fs::create_dir_all(dir.join("remote/src")).unwrap();
+        fs::write(dir.join("remote/src/main.rs"), "fn main() {}\n").unwrap();
+        fs::write(
+            dir.join("remote/Cargo.toml"),
+            "[package]\nname = \"remote\"\n\n[dependencies]\nregex = \"1\"\n",
+        )
+        .unwrap();
+        // Every exercise but `untested` can be reset
+        for file in [
+            "good.rs",
+            "linted.rs",
+            "remote/src/main.rs",
+            "remote/Cargo.toml",
+        ] {
+            let original = root.join("originals").join(file);
+            fs::create_dir_all(original.parent().unwrap()).unwrap();
+            fs::copy(dir.join(file), original).unwrap();
+        }
+        let info = format!(
+            r#"
+editable_tests = ["good", "unknown"]
+
+[[exercises]]
+name = "good"
+path = "{dir}/good.rs"
+mode = "test"
+hint = "A hint."
+
+[[exercises]]
+name = "good"
+path = "{dir}/untested.rs"
+mode = "test"
+hint = ""
+
+[[exercises]]
+name = "linted"
+path = "{dir}/linted.rs"
+mode = "clippy"
+hint = {{ en = "A hint.", fr = "Un indice." }}
+
+[[exercises]]
+name = "remote"
+path = "{dir}/remote"
+mode = "cargo"
+cargo = "run"
+hint.zh = "一个提示。"
+
+[[exercises]]
+name = "unhashed"
+path = "{dir}/good.rs"
+mode = "test"
+hint = "A hint."
+
+[[exercises]]
+name = "missing"
+path = "{dir}/missing.rs"
+mode = "compile"
+miri = true
+hint = "A hint."
+"#,
+            dir = dir.display()
+        );
+
+        assert_eq!(
+            check(&info, &dir),
+            [
+                String::from("good: there is more than one exercise named `good`"),
+                String::from("good: the exercise has no hint"),
+                format!(
+                    "good: there is no pristine copy of {}/untested.rs at {}/originals/untested.rs",
+                    dir.display(),
+                    root.display()
+                ),
+                String::from("good: test exercises need at least one #[test]"),
+                String::from(
+                    "linted: unknown locale `fr` in a hint, expected one of en, zh-CN, zh"
+                ),
+                format!(
+                    "linted: clippy exercises belong in the clippy directory, not in {}",
+                    dir.display()
+                ),
+                String::from("remote: a hint given per locale has no `en` text to fall back to"),
+                format!(
+                    "remote: the dependency `regex` in {}/remote/Cargo.toml has no path, but only local dependencies can be built offline",
+                    dir.display()
+                ),
+                String::from(
+                    "unhashed: the tests have no tests_sha256, add `tests_sha256 = \"e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855\"` or list the exercise in editable_tests"
+                ),
+                String::from("missing: only exercises with tests can run under Miri"),
+                format!("missing: {}/missing.rs does not exist", dir.display()),
+                String::from("editable_tests: there is no exercise named `unknown`"),
+                format!(
+                    "{}/orphan.rs is not part of any exercise in info.toml",
+                    dir.display()
+                ),
+            ]
+        );
+
+        let unknown_mode = info.replace("mode = \"clippy\"", "mode = \"lint\"");
+        assert_eq!(
+            check(&unknown_mode, &dir),
+            ["linted: unknown mode `lint`, expected one of compile, test, clippy, buildscript, cargo"]
+        );
+        assert!(check("[[exercises]", &dir)[0].starts_with("info.toml is not valid TOML"));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -1,20 +1,38 @@
-use crate::exercise::{CompiledExercise, Exercise, Mode, State};
+use crate::cache::Cache;
+use crate::diagnostics;
+use crate::diff::output_diff;
+use crate::exercise::{Attempt, CargoCommand, Exercise, ExerciseOutput, MiriOutcome, Mode, State};
+use crate::grade::{self, ExerciseResult};
+use crate::harness::{self, TestStatus};
+use crate::progress::Progress;
 use console::style;
 use indicatif::{ProgressBar, ProgressStyle};
 use std::env;
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::time::Instant;
+
+// The code of the last compiler error shown, for `rustc --explain`
+static LAST_ERROR_CODE: Mutex<Option<String>> = Mutex::new(None);
 
 // Verify that the provided container of Exercise objects
 // can be compiled and run without any failures.
 // Any such failures will be reported to the end user.
 // If the Exercise being verified is a test, the verbose boolean
 // determines whether or not the test harness outputs are displayed.
+// Compiler errors are shown one at a time unless raw_diagnostics is set.
+// Exercises that passed before and haven't changed since are taken from the cache.
+// The outcome of every exercise is recorded in progress.
 pub fn verify<'a>(
     exercises: impl IntoIterator<Item = &'a Exercise>,
-    progress: (usize, usize),
+    (num_done, total): (usize, usize),
     verbose: bool,
     success_hints: bool,
-) -> Result<(), &'a Exercise> {
-    let (num_done, total) = progress;
+    raw_diagnostics: bool,
+    cache: &Cache,
+    progress: &mut Progress,
+) -> Verified<'a> {
+    *LAST_ERROR_CODE.lock().unwrap() = None;
     let bar = ProgressBar::new(total as u64);
     let mut percentage = num_done as f32 / total as f32 * 100.0;
     bar.set_style(ProgressStyle::default_bar()
@@ -24,125 +42,277 @@ pub fn verify<'a>(
     bar.set_position(num_done as u64);
     bar.set_message(format!("({:.1} %)", percentage));
 
+    let mut results = Vec::new();
     for exercise in exercises {
-        let compile_result = match exercise.mode {
-            Mode::Test => compile_and_test(exercise, RunMode::Interactive, verbose, success_hints),
-            Mode::Compile => compile_and_run_interactively(exercise, success_hints),
-            Mode::Clippy => compile_only(exercise, success_hints),
-            Mode::BuildScript => compile_and_test(exercise, RunMode::Interactive, verbose, success_hints),
-
+        let progress_bar = spinner(exercise);
+        let start = Instant::now();
+        let attempt = attempt(exercise, cache, true);
+        progress_bar.finish_and_clear();
+        let cached = matches!(attempt, Attempt::Cached(_));
+        record(exercise, attempt.passed(), cached, progress);
+        let mut result = ExerciseResult {
+            hints_revealed: progress.hints_revealed(exercise),
+            ..grade::result(exercise, &attempt, start.elapsed())
+        };
+        let reported = report(
+            exercise,
+            attempt,
+            RunMode::Interactive,
+            verbose,
+            raw_diagnostics,
+        );
+        let done = match reported {
+            Ok(output) => prompt_for_completion(exercise, output, success_hints),
+            Err(()) => false,
         };
-        if !compile_result.unwrap_or(false) {
-            return Err(exercise);
+        // It may pass, but the learner hasn't removed `I AM NOT DONE` yet
+        if !done && result.result {
+            result.result = false;
+            result.earned_points = 0.0;
+        }
+        results.push(result);
+        if !done {
+            return Verified {
+                results,
+                stopped_at: Some(exercise),
+            };
         }
         percentage += 100.0 / total as f32;
         bar.inc(1);
         bar.set_message(format!("({:.1} %)", percentage));
     }
-    Ok(())
+    Verified {
+        results,
+        stopped_at: None,
+    }
 }
 
-enum RunMode {
-    Interactive,
-    NonInteractive,
+// What came of verifying exercises: the results of the ones verified, for
+// reports, and the exercise it stopped at, if it didn't get through them all
+pub struct Verified<'a> {
+    pub results: Vec<ExerciseResult>,
+    pub stopped_at: Option<&'a Exercise>,
 }
 
-// Compile and run the resulting test harness of the given Exercise
-pub fn test(exercise: &Exercise, verbose: bool) -> Result<(), ()> {
-    compile_and_test(exercise, RunMode::NonInteractive, verbose, false)?;
-    Ok(())
+// Verify an exercise. One that passed before and hasn't changed since is
+// taken from the cache, unless use_cache is false, and the outcome of the
+// others is cached.
+pub fn attempt(exercise: &Exercise, cache: &Cache, use_cache: bool) -> Attempt {
+    if use_cache {
+        if let Some(entry) = cache.lookup(exercise).filter(|entry| entry.passed) {
+            return Attempt::Cached(entry.output);
+        }
+    }
+    let attempt = exercise.attempt();
+    let output = match &attempt {
+        Attempt::Ran(Ok(output)) if runs_program(exercise) => Some(output.stdout.clone()),
+        _ => None,
+    };
+    cache.store(exercise, attempt.passed(), output);
+    attempt
 }
 
-// Invoke the rust compiler without running the resulting binary
-fn compile_only(exercise: &Exercise, success_hints: bool) -> Result<bool, ()> {
-    let progress_bar = ProgressBar::new_spinner();
-    progress_bar.set_message(format!("Compiling {exercise}..."));
-    progress_bar.enable_steady_tick(100);
-
-    let _ = compile(exercise, &progress_bar)?;
-    progress_bar.finish_and_clear();
+// Record whether an exercise passed in the progress. An outcome taken from
+// the cache is only recorded if the exercise hasn't been verified yet.
+pub fn record(exercise: &Exercise, passed: bool, cached: bool, progress: &mut Progress) {
+    if !cached || !progress.verified(exercise) {
+        progress.record(exercise, passed);
+    }
+}
 
-    Ok(prompt_for_completion(exercise, None, success_hints))
+// Whether the exercise runs as a program, rather than as tests
+fn runs_program(exercise: &Exercise) -> bool {
+    matches!(
+        (exercise.mode, exercise.cargo_command()),
+        (Mode::Compile, _) | (Mode::Cargo, CargoCommand::Run)
+    )
 }
 
-// Compile the given Exercise and run the resulting binary in an interactive mode
-fn compile_and_run_interactively(exercise: &Exercise, success_hints: bool) -> Result<bool, ()> {
+fn spinner(exercise: &Exercise) -> ProgressBar {
     let progress_bar = ProgressBar::new_spinner();
-    progress_bar.set_message(format!("Compiling {exercise}..."));
+    let message = match exercise.mode {
+        Mode::Test | Mode::BuildScript => t!("verify.testing", exercise = exercise),
+        Mode::Cargo if exercise.cargo_command() == CargoCommand::Test => {
+            t!("verify.testing", exercise = exercise)
+        }
+        _ => t!("verify.compiling", exercise = exercise),
+    };
+    progress_bar.set_message(message);
     progress_bar.enable_steady_tick(100);
+    progress_bar
+}
 
-    let compilation = compile(exercise, &progress_bar)?;
+enum RunMode {
+    Interactive,
+    NonInteractive,
+}
 
-    progress_bar.set_message(format!("Running {exercise}..."));
-    let result = compilation.run();
+// Compile and run the resulting test harness of the given Exercise
+pub fn test(exercise: &Exercise, verbose: bool, raw_diagnostics: bool) -> Result<(), ()> {
+    let progress_bar = spinner(exercise);
+    let attempt = exercise.attempt();
     progress_bar.finish_and_clear();
+    report(
+        exercise,
+        attempt,
+        RunMode::NonInteractive,
+        verbose,
+        raw_diagnostics,
+    )?;
+    Ok(())
+}
 
-    let output = match result {
-        Ok(output) => output,
-        Err(output) => {
-            warn!("Ran {} with errors", exercise);
-            println!("{}", output.stdout);
-            println!("{}", output.stderr);
+// Show the user what came of an attempt. Exercises that run as a program
+// pass on what they printed. The output of test harnesses is displayed if
+// verbose is set to true.
+fn report(
+    exercise: &Exercise,
+    attempt: Attempt,
+    run_mode: RunMode,
+    verbose: bool,
+    raw_diagnostics: bool,
+) -> Result<Option<String>, ()> {
+    let result = match attempt {
+        Attempt::Cached(output) => return Ok(output),
+        Attempt::CompileFailed(output) => {
+            if output.timed_out {
+                warn_timed_out(exercise);
+            } else {
+                warn!("{}", t!("verify.compile_failed", exercise = exercise));
+                print_compile_error(&output, raw_diagnostics);
+            }
             return Err(());
         }
+        Attempt::Ran(result) => result,
     };
 
-    Ok(prompt_for_completion(exercise, Some(output.stdout), success_hints))
-}
-
-// Compile the given Exercise as a test harness and display
-// the output if verbose is set to true
-fn compile_and_test(exercise: &Exercise, run_mode: RunMode, verbose: bool, success_hints: bool) -> Result<bool, ()> {
-    let progress_bar = ProgressBar::new_spinner();
-    progress_bar.set_message(format!("Testing {exercise}..."));
-    progress_bar.enable_steady_tick(100);
-
-    let compilation = compile(exercise, &progress_bar)?;
-    let result = compilation.run();
-    progress_bar.finish_and_clear();
-
     match result {
+        Ok(_) if exercise.only_linted() => Ok(None),
+        Ok(output) if runs_program(exercise) => Ok(Some(output.stdout)),
         Ok(output) => {
             if verbose {
                 println!("{}", output.stdout);
             }
-            if let RunMode::Interactive = run_mode {
-                Ok(prompt_for_completion(exercise, None, success_hints))
-            } else {
-                Ok(true)
+            if let RunMode::NonInteractive = run_mode {
+                print_test_breakdown(&output.stdout);
+            }
+            if output.miri == MiriOutcome::Unavailable {
+                warn_miri_unavailable(exercise);
             }
+            Ok(None)
+        }
+        Err(output) if output.timed_out => {
+            warn_timed_out(exercise);
+            println!("{}", output.stdout);
+            Err(())
+        }
+        Err(output) if output.miri == MiriOutcome::Failed => {
+            warn!("{}", t!("verify.miri_failed", exercise = exercise));
+            println!("{}", output.stderr);
+            Err(())
+        }
+        Err(output) if output.unexpected_output => {
+            warn!("{}", t!("verify.wrong_output", exercise = exercise));
+            println!("{}", output_diff(exercise, &output.stdout));
+            Err(())
+        }
+        Err(output) if runs_program(exercise) => {
+            warn!("{}", t!("verify.run_failed", exercise = exercise));
+            println!("{}", output.stdout);
+            println!("{}", output.stderr);
+            Err(())
         }
         Err(output) => {
-            warn!(
-                "Testing of {} failed! Please try again. Here's the output:",
-                exercise
-            );
+            warn!("{}", t!("verify.test_failed", exercise = exercise));
             println!("{}", output.stdout);
+            if let RunMode::NonInteractive = run_mode {
+                print_test_breakdown(&output.stdout);
+            }
             Err(())
         }
     }
 }
 
-// Compile the given Exercise and return an object with information
-// about the state of the compilation
-fn compile<'a, 'b>(
-    exercise: &'a Exercise,
-    progress_bar: &'b ProgressBar,
-) -> Result<CompiledExercise<'a>, ()> {
-    let compilation_result = exercise.compile();
+// Show why an exercise failed to compile. Unless raw diagnostics were asked
+// for, only the first error is shown, which is the one to fix first anyway.
+pub fn print_compile_error(output: &ExerciseOutput, raw_diagnostics: bool) {
+    println!("{}", compile_error(output, raw_diagnostics));
+}
 
-    match compilation_result {
-        Ok(compilation) => Ok(compilation),
-        Err(output) => {
-            progress_bar.finish_and_clear();
-            warn!(
-                "Compiling of {} failed! Please try again. Here's the output:",
-                exercise
-            );
-            println!("{}", output.stderr);
-            Err(())
+// The compiler errors of an exercise the way print_compile_error shows them
+pub fn compile_error(output: &ExerciseOutput, raw_diagnostics: bool) -> String {
+    *LAST_ERROR_CODE.lock().unwrap() =
+        diagnostics::first_error_code(&output.diagnostics).map(str::to_string);
+    match diagnostics::render_focused(&output.diagnostics) {
+        Some(focused) if !raw_diagnostics => focused,
+        _ => output.stderr.clone(),
+    }
+}
+
+// The code of the last compiler error that was shown
+pub fn last_error_code() -> Option<String> {
+    LAST_ERROR_CODE.lock().unwrap().clone()
+}
+
+// List which of the tests in a harness's output passed
+fn print_test_breakdown(stdout: &str) {
+    print!("{}", test_breakdown(stdout));
+}
+
+pub fn test_breakdown(stdout: &str) -> String {
+    let tests = harness::parse(stdout);
+    let mut out = String::new();
+    if tests.is_empty() {
+        return out;
+    }
+    let passed = tests
+        .iter()
+        .filter(|t| t.status == TestStatus::Passed)
+        .count();
+    let counted = tests
+        .iter()
+        .filter(|t| t.status != TestStatus::Ignored)
+        .count();
+    let _ = writeln!(
+        out,
+        "{}",
+        t!("verify.tests_passed", passed = passed, counted = counted)
+    );
+    for test in &tests {
+        let _ = match test.status {
+            TestStatus::Passed => writeln!(out, "  {} {}", style("✓").green(), test.name),
+            TestStatus::Ignored => {
+                let ignored = t!("verify.ignored");
+                writeln!(out, "  {} {} {ignored}", style("-").yellow(), test.name)
+            }
+            TestStatus::Failed => writeln!(out, "  {} {}", style("✗").red(), test.name),
+        };
+        if let Some(first_line) = test.message.as_deref().and_then(|m| m.lines().next()) {
+            let _ = writeln!(out, "      {}", style(first_line).dim());
         }
     }
+    out
+}
+
+// Tell the user that compiling or running an exercise was stopped
+pub fn warn_timed_out(exercise: &Exercise) {
+    warn!("{}", t!("verify.timed_out", exercise = exercise));
+    println!("{}", timed_out_advice(exercise));
+}
+
+fn warn_miri_unavailable(exercise: &Exercise) {
+    warn!("{}", miri_unavailable_advice(exercise));
+}
+
+pub fn miri_unavailable_advice(exercise: &Exercise) -> String {
+    t!("verify.miri_unavailable", exercise = exercise)
+}
+
+pub fn timed_out_advice(exercise: &Exercise) -> String {
+    t!(
+        "verify.timed_out_advice",
+        seconds = exercise.timeout().as_secs()
+    )
 }
 
 fn prompt_for_completion(exercise: &Exercise, prompt_output: Option<String>, success_hints: bool) -> bool {
@@ -150,26 +320,31 @@ fn prompt_for_completion(exercise: &Exercise, prompt_output: Option<String>, suc
         State::Done => return true,
         State::Pending(context) => context,
     };
-    match exercise.mode {
-        Mode::Compile => success!("Successfully ran {}!", exercise),
-        Mode::Test => success!("Successfully tested {}!", exercise),
-        Mode::Clippy => success!("Successfully compiled {}!", exercise),
-        Mode::BuildScript => success!("Successfully compiled {}!", exercise),
+    match (exercise.mode, exercise.cargo_command()) {
+        (Mode::Compile, _) | (Mode::Cargo, CargoCommand::Run) => {
+            success!("{}", t!("verify.ran", exercise = exercise))
+        }
+        (Mode::Test, _) | (Mode::Cargo, CargoCommand::Test) => {
+            success!("{}", t!("verify.tested", exercise = exercise))
+        }
+        (Mode::Clippy | Mode::BuildScript, _) | (Mode::Cargo, CargoCommand::Clippy) => {
+            success!("{}", t!("verify.compiled", exercise = exercise))
+        }
     }
 
     let no_emoji = env::var("NO_EMOJI").is_ok();
 
     let clippy_success_msg = if no_emoji {
-        "The code is compiling, and Clippy is happy!"
+        t!("verify.clippy_happy")
     } else {
-        "The code is compiling, and 📎 Clippy 📎 is happy!"
+        t!("verify.clippy_happy_emoji")
     };
 
-    let success_msg = match exercise.mode {
-        Mode::Compile => "The code is compiling!",
-        Mode::Test => "The code is compiling, and the tests pass!",
-        Mode::Clippy => clippy_success_msg,
-        Mode::BuildScript => "Build script works!",
+    let success_msg = match (exercise.mode, exercise.cargo_command()) {
+        (Mode::Compile, _) | (Mode::Cargo, CargoCommand::Run) => t!("verify.compiles"),
+        (Mode::Test, _) | (Mode::Cargo, CargoCommand::Test) => t!("verify.tests_pass"),
+        (Mode::Clippy, _) | (Mode::Cargo, CargoCommand::Clippy) => clippy_success_msg,
+        (Mode::BuildScript, _) => t!("verify.build_script_works"),
     };
     println!();
     if no_emoji {
@@ -180,24 +355,27 @@ fn prompt_for_completion(exercise: &Exercise, prompt_output: Option<String>, suc
     println!();
 
     if let Some(output) = prompt_output {
-        println!("Output:");
+        println!("{}", t!("verify.output"));
         println!("{}", separator());
         println!("{output}");
         println!("{}", separator());
         println!();
     }
     if success_hints {
-        println!("Hints:");
+        println!("{}", t!("verify.hints"));
         println!("{}", separator());
-        println!("{}", exercise.hint);
+        println!("{}", exercise.hints().join("\n\n"));
         println!("{}", separator());
         println!();
     }
 
-    println!("You can keep working on this exercise,");
+    println!("{}", t!("verify.keep_working"));
     println!(
-        "or jump into the next one by removing the {} comment:",
-        style("`I AM NOT DONE`").bold()
+        "{}",
+        t!(
+            "verify.remove_marker",
+            marker = style("`I AM NOT DONE`").bold()
+        )
     );
     println!();
     for context_line in context {
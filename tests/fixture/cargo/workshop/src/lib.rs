@@ -0,0 +1,14 @@
+pub fn quadruple(n: u32) -> u32 {
+    n * 4
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "fast")]
+    fn quadruple_is_double_double() {
+        assert_eq!(quadruple(3), tools::double(tools::double(3)));
+    }
+}
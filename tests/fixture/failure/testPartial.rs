@@ -0,0 +1,9 @@
+#[test]
+fn passing() {
+    assert!(true);
+}
+
+#[test]
+fn failing() {
+    assert_eq!(1 + 1, 3, "one plus one");
+}
@@ -0,0 +1,5 @@
+#[cfg(feature = "fast")]
+fn main() {
+    let max: u8 = 255;
+    println!("{}", max + std::hint::black_box(1));
+}
@@ -0,0 +1,10 @@
+fn round_trip(value: u32) -> u32 {
+    let raw = Box::into_raw(Box::new(value));
+    // SAFETY: `raw` came from `Box::into_raw` and is turned back into a box once
+    *unsafe { Box::from_raw(raw) }
+}
+
+#[test]
+fn round_trip_keeps_the_value() {
+    assert_eq!(round_trip(7), 7);
+}
@@ -0,0 +1,6 @@
+mod prices;
+
+#[test]
+fn apples_are_cheap() {
+    assert_eq!(prices::apple(), 1);
+}
@@ -0,0 +1,3 @@
+pub fn apple() -> u32 {
+    1
+}
@@ -0,0 +1,11 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+
+fn main() {
+    // The test tells the exercise where the project is
+    let project = env::var("RUSTLINGS_TEST_PROJECT").unwrap();
+    let _ = fs::write(Path::new(&project).join("tampered"), "");
+    // The working directory is the exercise's own to write to
+    fs::write("scratch", "").unwrap();
+}
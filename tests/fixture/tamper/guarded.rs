@@ -0,0 +1,13 @@
+fn answer() -> u32 {
+    41
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_the_answer() {
+        assert_eq!(answer(), 42);
+    }
+}
@@ -1,8 +1,10 @@
 use assert_cmd::prelude::*;
 use glob::glob;
 use predicates::boolean::PredicateBooleanExt;
-use std::fs::File;
+use std::env;
+use std::fs::{self, File};
 use std::io::Read;
+use std::path::Path;
 use std::process::Command;
 
 #[test]
@@ -44,7 +46,7 @@ fn verify_fails_if_some_fails() {
 fn run_single_compile_success() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "compSuccess"])
+        .args(["run", "compSuccess"])
         .current_dir("tests/fixture/success/")
         .assert()
         .success();
@@ -54,17 +56,42 @@ fn run_single_compile_success() {
 fn run_single_compile_failure() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "compFailure"])
+        .args(["run", "compFailure"])
         .current_dir("tests/fixture/failure/")
         .assert()
         .code(1);
 }
 
+#[test]
+fn run_single_compile_failure_shows_first_error() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "compFailure"])
+        .current_dir("tests/fixture/failure/")
+        .assert()
+        .code(1)
+        .stdout(
+            predicates::str::contains("expected pattern")
+                .and(predicates::str::contains("aborting due to").not()),
+        );
+}
+
+#[test]
+fn run_single_compile_failure_raw_diagnostics() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--raw-diagnostics", "run", "compFailure"])
+        .current_dir("tests/fixture/failure/")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("aborting due to"));
+}
+
 #[test]
 fn run_single_test_success() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "testSuccess"])
+        .args(["run", "testSuccess"])
         .current_dir("tests/fixture/success/")
         .assert()
         .success();
@@ -74,22 +101,58 @@ fn run_single_test_success() {
 fn run_single_test_failure() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "testFailure"])
+        .args(["run", "testFailure"])
         .current_dir("tests/fixture/failure/")
         .assert()
         .code(1);
 }
 
+#[test]
+fn run_single_test_partially_passed() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "testPartial"])
+        .current_dir("tests/fixture/failure/")
+        .assert()
+        .code(1)
+        .stdout(
+            predicates::str::contains("1 of 2 tests passed")
+                .and(predicates::str::contains("one plus one")),
+        );
+}
+
 #[test]
 fn run_single_test_not_passed() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "testNotPassed.rs"])
+        .args(["run", "testNotPassed.rs"])
         .current_dir("tests/fixture/failure/")
         .assert()
         .code(1);
 }
 
+#[test]
+fn run_single_compile_timeout() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "compLoop"])
+        .current_dir("tests/fixture/timeout/")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("timed out"));
+}
+
+#[test]
+fn run_single_test_timeout() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "testLoop"])
+        .current_dir("tests/fixture/timeout/")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("timed out"));
+}
+
 #[test]
 fn run_single_test_no_filename() {
     Command::cargo_bin("rustlings")
@@ -104,7 +167,7 @@ fn run_single_test_no_filename() {
 fn run_single_test_no_exercise() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "compNoExercise.rs"])
+        .args(["run", "compNoExercise.rs"])
         .current_dir("tests/fixture/failure")
         .assert()
         .code(1);
@@ -112,11 +175,350 @@ fn run_single_test_no_exercise() {
 
 #[test]
 fn reset_single_exercise() {
+    // Work on a copy, so that the fixture itself is never changed
+    let dir = env::temp_dir().join(format!("rustlings-reset-{}", std::process::id()));
+    fs::create_dir_all(dir.join("originals")).unwrap();
+    for file in [
+        "info.toml",
+        "compSuccess.rs",
+        "testSuccess.rs",
+        "originals/compSuccess.rs",
+    ] {
+        fs::copy(Path::new("tests/fixture/success").join(file), dir.join(file)).unwrap();
+    }
+    let original = fs::read_to_string(dir.join("compSuccess.rs")).unwrap();
+
+    // Nothing is copied behind the learner's back
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["list"])
+        .current_dir(&dir)
+        .assert()
+        .success();
+    assert!(!dir.join(".rustlings").exists());
+    fs::write(dir.join("compSuccess.rs"), "// my attempt\n").unwrap();
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["reset", "compSuccess"])
+        .current_dir(&dir)
+        .with_stdin()
+        .buffer("n\n")
+        .assert()
+        .code(1);
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["reset", "--yes", "compSuccess"])
+        .current_dir(&dir)
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("your version was saved to"));
+
+    assert_eq!(fs::read_to_string(dir.join("compSuccess.rs")).unwrap(), original);
+    let backups: Vec<_> = fs::read_dir(dir.join(".rustlings/backups"))
+        .unwrap()
+        .map(|entry| fs::read_to_string(entry.unwrap().path()).unwrap())
+        .collect();
+    assert_eq!(backups, ["// my attempt\n"]);
+
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["reset", "--yes", "testSuccess"])
+        .current_dir(&dir)
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(
+            "there is no pristine copy of testSuccess.rs at originals/testSuccess.rs",
+        ));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn exercises_are_locked_by_their_prerequisites() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["list"])
+        .current_dir("tests/fixture/requires/")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Locked (requires testSuccess)"));
+}
+
+#[test]
+fn prerequisite_cycles_are_rejected() {
+    let dir = env::temp_dir().join(format!("rustlings-requires-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(
+        dir.join("info.toml"),
+        "[[exercises]]\nname = \"a\"\npath = \"a.rs\"\nmode = \"compile\"\nrequires = [\"b\"]\n\n\
+         [[exercises]]\nname = \"b\"\npath = \"b.rs\"\nmode = \"compile\"\nrequires = [\"a\"]\n",
+    )
+    .unwrap();
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["list"])
+        .current_dir(&dir)
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(
+            "info.toml is invalid: the prerequisites form a cycle: a -> b -> a",
+        ));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn validate_reports_mistakes() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["validate"])
+        .current_dir("tests/fixture/invalid/")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(
+            "compSuccess: unknown mode `compiel`",
+        ));
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["list"])
+        .current_dir("tests/fixture/invalid/")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("Run `rustlings validate`"));
+}
+
+#[test]
+fn check_solutions_reports_exercises_that_pass_as_shipped() {
+    // `fixed` was solved in exercises/, but its copy in originals/ fails.
+    // `unshipped` has no copy there, so it can't be checked.
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["check-solutions"])
+        .current_dir("tests/fixture/solutions/")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("fixed:").not())
+        .stdout(predicates::str::contains(
+            "starter: the unmodified exercise already passes",
+        ))
+        .stdout(predicates::str::contains(
+            "unshipped: there is no pristine copy of the exercise at originals/unshipped.rs",
+        ))
+        .stdout(predicates::str::contains("2 of 3 exercises have a problem."));
+}
+
+#[test]
+fn run_compile_exercise_with_unexpected_output() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "greeting"])
+        .current_dir("tests/fixture/output/")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("- Hello world!"))
+        .stdout(predicates::str::contains("+ Hello there!"));
+}
+
+#[test]
+fn run_compile_exercise_with_stdin() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "echo"])
+        .current_dir("tests/fixture/output/")
+        .assert()
+        .success();
+}
+
+#[test]
+fn run_exercise_with_its_own_edition() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "edition2015"])
+        .current_dir("tests/fixture/flags/")
+        .assert()
+        .success();
+}
+
+#[test]
+fn run_exercise_with_cfg_and_rustc_args() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "configured"])
+        .current_dir("tests/fixture/flags/")
+        .assert()
+        .success()
+        .stdout(predicates::str::starts_with("0\n"));
+}
+
+#[test]
+fn run_exercise_with_denied_lint() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "denied"])
+        .current_dir("tests/fixture/flags/")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("unused variable"));
+}
+
+#[test]
+fn run_directory_exercise_with_modules() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "garden"])
+        .current_dir("tests/fixture/modules/")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("carrot"));
+}
+
+#[test]
+fn test_directory_exercise_with_lib_root() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "shop"])
+        .current_dir("tests/fixture/modules/")
+        .assert()
+        .success();
+}
+
+#[test]
+fn test_cargo_exercise_with_path_dependency() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "workshop"])
+        .current_dir("tests/fixture/cargo/")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("quadruple_is_double_double"));
+}
+
+#[test]
+fn run_cargo_exercise_with_expected_output() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "greeter"])
+        .current_dir("tests/fixture/cargo/")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Hello, 4 times!"));
+}
+
+#[test]
+fn validate_cargo_exercises() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["validate"])
+        .current_dir("tests/fixture/cargo/")
+        .assert()
+        .success();
+}
+
+#[test]
+fn test_miri_exercise_with_or_without_miri() {
+    // Without Miri the tests still run, and a warning says what was skipped
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "raw"])
+        .current_dir("tests/fixture/miri/")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("round_trip_keeps_the_value"));
+}
+
+#[test]
+fn exercises_cannot_write_to_the_project() {
+    let dir = env::temp_dir().join(format!("rustlings-sandbox-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    for file in ["info.toml", "tamper.rs"] {
+        fs::copy(Path::new("tests/fixture/sandbox").join(file), dir.join(file)).unwrap();
+    }
+
+    let output = Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["verify"])
+        .env("RUSTLINGS_TEST_PROJECT", &dir)
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    // Without namespaces only the warning is left to check
+    let unconfined = String::from_utf8_lossy(&output.stdout).contains("can't be sandboxed");
+    assert!(unconfined || !dir.join("tampered").exists());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn grading_flags_changed_tests() {
+    let dir = env::temp_dir().join(format!("rustlings-tamper-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::copy("tests/fixture/tamper/info.toml", dir.join("info.toml")).unwrap();
+    // The tests now expect the wrong answer, so they pass
+    let source = fs::read_to_string("tests/fixture/tamper/guarded.rs").unwrap();
+    fs::write(dir.join("guarded.rs"), source.replace("42", "41")).unwrap();
+
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["cicvverify"])
+        .current_dir(&dir)
+        .assert()
+        .code(1);
+    let report = fs::read_to_string(dir.join(".github/result/check_result.json")).unwrap();
+    let report: serde_json::Value = serde_json::from_str(&report).unwrap();
+    let result = &report["exercises"][0];
+    assert_eq!(result["result"], false);
+    assert_eq!(result["tampered"], true);
+    assert_eq!(result["phase"], "tampered");
+
+    // `verify` lets the exercise pass, but doesn't report it as passed
+    let report = dir.join("verify.json");
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["reset", "intro1"])
+        .args(["verify", "--report", "json", "--output"])
+        .arg(&report)
+        .current_dir(&dir)
         .assert()
-        .code(0);
+        .success();
+    let report: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&report).unwrap()).unwrap();
+    let result = &report["exercises"][0];
+    assert_eq!(result["result"], false);
+    assert_eq!(result["phase"], "tampered");
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn reset_directory_exercise() {
+    let dir = env::temp_dir().join(format!("rustlings-reset-dir-{}", std::process::id()));
+    fs::create_dir_all(dir.join("garden")).unwrap();
+    fs::create_dir_all(dir.join("originals/garden")).unwrap();
+    for file in [
+        "info.toml",
+        "garden/main.rs",
+        "garden/vegetables.rs",
+        "originals/garden/main.rs",
+        "originals/garden/vegetables.rs",
+    ] {
+        fs::copy(Path::new("tests/fixture/modules").join(file), dir.join(file)).unwrap();
+    }
+    fs::create_dir_all(dir.join("shop")).unwrap();
+    for file in ["shop/lib.rs", "shop/prices.rs"] {
+        fs::copy(Path::new("tests/fixture/modules").join(file), dir.join(file)).unwrap();
+    }
+
+    fs::remove_file(dir.join("garden/vegetables.rs")).unwrap();
+    fs::write(dir.join("garden/fruits.rs"), "// my attempt\n").unwrap();
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["reset", "--yes", "garden"])
+        .current_dir(&dir)
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Removed garden/fruits.rs"));
+
+    assert!(dir.join("garden/vegetables.rs").exists());
+    assert!(!dir.join("garden/fruits.rs").exists());
+    fs::remove_dir_all(&dir).unwrap();
 }
 
 #[test]
@@ -127,7 +529,7 @@ fn reset_no_exercise() {
         .assert()
         .code(1)
         .stderr(predicates::str::contains(
-            "positional arguments not provided",
+            "Name one exercise to reset, or use either --all or --category.",
         ));
 }
 
@@ -135,7 +537,7 @@ fn reset_no_exercise() {
 fn get_hint_for_single_test() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["hint", "testFailure"])
+        .args(["hint", "testFailure"])
         .current_dir("tests/fixture/failure")
         .assert()
         .code(0)
@@ -171,7 +573,7 @@ fn all_exercises_require_confirmation() {
 fn run_compile_exercise_does_not_prompt() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "pending_exercise"])
+        .args(["run", "pending_exercise"])
         .current_dir("tests/fixture/state")
         .assert()
         .code(0)
@@ -182,7 +584,7 @@ fn run_compile_exercise_does_not_prompt() {
 fn run_test_exercise_does_not_prompt() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "pending_test_exercise"])
+        .args(["run", "pending_test_exercise"])
         .current_dir("tests/fixture/state")
         .assert()
         .code(0)
@@ -193,7 +595,7 @@ fn run_test_exercise_does_not_prompt() {
 fn run_single_test_success_with_output() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["--nocapture", "run", "testSuccess"])
+        .args(["--nocapture", "run", "testSuccess"])
         .current_dir("tests/fixture/success/")
         .assert()
         .code(0)
@@ -204,7 +606,7 @@ fn run_single_test_success_with_output() {
 fn run_single_test_success_without_output() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "testSuccess"])
+        .args(["run", "testSuccess"])
         .current_dir("tests/fixture/success/")
         .assert()
         .code(0)
@@ -215,7 +617,7 @@ fn run_single_test_success_without_output() {
 fn run_rustlings_list() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["list"])
+        .args(["list"])
         .current_dir("tests/fixture/success")
         .assert()
         .success();
@@ -225,7 +627,7 @@ fn run_rustlings_list() {
 fn run_rustlings_list_no_pending() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["list"])
+        .args(["--legacy-marker", "list"])
         .current_dir("tests/fixture/success")
         .assert()
         .success()
@@ -236,7 +638,7 @@ fn run_rustlings_list_no_pending() {
 fn run_rustlings_list_both_done_and_pending() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["list"])
+        .args(["--legacy-marker", "list"])
         .current_dir("tests/fixture/state")
         .assert()
         .success()
@@ -247,7 +649,7 @@ fn run_rustlings_list_both_done_and_pending() {
 fn run_rustlings_list_without_pending() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["list", "--solved"])
+        .args(["--legacy-marker", "list", "--solved"])
         .current_dir("tests/fixture/state")
         .assert()
         .success()
@@ -258,9 +660,235 @@ fn run_rustlings_list_without_pending() {
 fn run_rustlings_list_without_done() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["list", "--unsolved"])
+        .args(["--legacy-marker", "list", "--unsolved"])
         .current_dir("tests/fixture/state")
         .assert()
         .success()
         .stdout(predicates::str::contains("Done").not());
 }
+
+#[test]
+fn verified_exercises_are_done() {
+    let _ = fs::remove_file("tests/fixture/progress/.rustlings-state.json");
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["list"])
+        .current_dir("tests/fixture/progress")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Done").not());
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "next"])
+        .current_dir("tests/fixture/progress")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Successfully ran compSuccess.rs"));
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["list", "--solved", "--names"])
+        .current_dir("tests/fixture/progress")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("compSuccess"));
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "next"])
+        .current_dir("tests/fixture/progress")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("tests passed"));
+    let state = fs::read_to_string("tests/fixture/progress/.rustlings-state.json").unwrap();
+    assert!(state.contains("\"attempts\": 1"));
+    assert!(state.contains("\"source_hash\""));
+}
+
+#[test]
+fn hints_are_revealed_one_attempt_at_a_time() {
+    let _ = fs::remove_file("tests/fixture/hints/.rustlings-state.json");
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["hint", "compSuccess"])
+        .current_dir("tests/fixture/hints")
+        .assert()
+        .success()
+        .stdout(
+            predicates::str::contains("Hint 1 of 3:\nFirst hint.")
+                .and(predicates::str::contains("Second hint.").not()),
+        );
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["hint", "compSuccess"])
+        .current_dir("tests/fixture/hints")
+        .assert()
+        .success()
+        .stdout(
+            predicates::str::contains("Try the exercise again")
+                .and(predicates::str::contains("Second hint.").not()),
+        );
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "compSuccess"])
+        .current_dir("tests/fixture/hints")
+        .assert()
+        .success();
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["hint", "compSuccess"])
+        .current_dir("tests/fixture/hints")
+        .assert()
+        .success()
+        .stdout(
+            predicates::str::contains("Hint 2 of 3:\nSecond hint.")
+                .and(predicates::str::contains("Third hint.").not()),
+        );
+}
+
+#[test]
+fn run_rustlings_list_as_json() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--legacy-marker", "list", "--format", "json"])
+        .current_dir("tests/fixture/state")
+        .assert()
+        .success()
+        .stdout(
+            predicates::str::contains(r#""name": "finished_exercise""#)
+                .and(predicates::str::contains(r#""done": true"#))
+                .and(predicates::str::contains(r#""mode": "test""#))
+                .and(predicates::str::contains("Progress:").not()),
+        );
+}
+
+#[test]
+fn run_rustlings_list_as_csv() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--legacy-marker", "list", "--format", "csv", "--solved"])
+        .current_dir("tests/fixture/state")
+        .assert()
+        .success()
+        .stdout(
+            "name,path,mode,category,done,locked_by,last_result,last_verified_at\n\
+             finished_exercise,finished_exercise.rs,compile,finished_exercise,true,,,\n",
+        );
+}
+
+#[test]
+fn verify_writes_reports() {
+    let junit = env::temp_dir().join(format!("rustlings-report-{}.xml", std::process::id()));
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["verify", "--report", "junit", "--output"])
+        .arg(&junit)
+        .current_dir("tests/fixture/failure")
+        .assert()
+        .code(1);
+    let xml = fs::read_to_string(&junit).unwrap();
+    fs::remove_file(&junit).unwrap();
+    assert!(xml.contains("<testsuites name=\"rustlings\" tests=\"1\" failures=\"1\""));
+    assert!(xml.contains("<testcase name=\"compFailure\""));
+    assert!(xml.contains("<failure type=\"compile\""));
+
+    let tap = env::temp_dir().join(format!("rustlings-report-{}.tap", std::process::id()));
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["verify", "--report", "tap", "--output"])
+        .arg(&tap)
+        .current_dir("tests/fixture/success")
+        .assert()
+        .success();
+    let report = fs::read_to_string(&tap).unwrap();
+    fs::remove_file(&tap).unwrap();
+    assert_eq!(
+        report,
+        "TAP version 13\n1..2\nok 1 - compSuccess\nok 2 - testSuccess\n"
+    );
+
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["verify", "--report", "xml"])
+        .current_dir("tests/fixture/success")
+        .assert()
+        .code(1)
+        .stderr(predicates::str::contains("expected json, junit or tap"));
+}
+
+#[test]
+fn grading_fails_under_the_required_score() {
+    let report = env::temp_dir().join(format!("rustlings-grade-{}.json", std::process::id()));
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["cicvverify", "--output"])
+        .arg(&report)
+        .current_dir("tests/fixture/failure")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("compFailure failed in"))
+        .stdout(predicates::str::contains("Graded 3 exercises in"))
+        .stdout(predicates::str::contains("100 % are required."));
+
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["cicvverify", "--quiet", "--fail-under", "10", "--output"])
+        .arg(&report)
+        .current_dir("tests/fixture/failure")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Graded").not());
+    let results: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&report).unwrap()).unwrap();
+    fs::remove_file(&report).unwrap();
+    assert_eq!(results["statistics"]["total_succeeds"], 0);
+}
+
+#[test]
+fn messages_and_hints_follow_the_locale() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--lang", "zh-CN", "hint", "compSuccess"])
+        .current_dir("tests/fixture/locales")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("读一读关于函数的那一章。"));
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["hint", "compSuccess"])
+        .env("RUSTLINGS_LANG", "en")
+        .current_dir("tests/fixture/locales")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Read the chapter on functions."));
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "compSuccess"])
+        .env_remove("RUSTLINGS_LANG")
+        .env("LANG", "zh_CN.UTF-8")
+        .current_dir("tests/fixture/locales")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("成功运行 compSuccess"));
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--lang", "zh-CN", "run", "greeting"])
+        .current_dir("tests/fixture/output/")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("- 期望的"))
+        .stdout(predicates::str::contains("+ 打印的"));
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--lang", "zh-CN", "validate"])
+        .current_dir("tests/fixture/output/")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("greeting：这个练习没有提示"))
+        .stdout(predicates::str::contains("发现 4 个问题。"));
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--lang", "fr", "list"])
+        .current_dir("tests/fixture/locales")
+        .assert()
+        .code(1)
+        .stderr(predicates::str::contains("expected en or zh-CN"));
+}